@@ -1,15 +1,752 @@
+/// Define a fieldless enum which is (de)serialized as the integer MaaCore uses
+///
+/// With a trailing `_ => Unknown`, values not listed are kept in an `Unknown(i32)` variant instead of failing
+#[cfg(any(feature = "task", feature = "message"))]
+macro_rules! int_enum {
+    ($(#[$meta:meta])* $name:ident { $($(#[$vmeta:meta])* $variant:ident = $value:expr,)* }) => {
+        $(#[$meta])*
+        #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+        #[serde(into = "i32", try_from = "i32")]
+        pub enum $name {
+            $($(#[$vmeta])* $variant = $value,)*
+        }
+
+        impl From<$name> for i32 {
+            fn from(value: $name) -> Self {
+                value as i32
+            }
+        }
+
+        impl TryFrom<i32> for $name {
+            type Error = String;
+
+            fn try_from(value: i32) -> Result<Self, Self::Error> {
+                match value {
+                    $($value => Ok($name::$variant),)*
+                    _ => Err(format!("Unknown {}: {}", stringify!($name), value)),
+                }
+            }
+        }
+    };
+    ($(#[$meta:meta])* $name:ident { $($(#[$vmeta:meta])* $variant:ident = $value:expr,)* _ => $unknown:ident, }) => {
+        $(#[$meta])*
+        #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+        #[serde(into = "i32", from = "i32")]
+        pub enum $name {
+            $($(#[$vmeta])* $variant,)*
+            /// A value unknown to this version
+            $unknown(i32),
+        }
+
+        impl From<$name> for i32 {
+            fn from(value: $name) -> Self {
+                match value {
+                    $($name::$variant => $value,)*
+                    $name::$unknown(value) => value,
+                }
+            }
+        }
+
+        impl From<i32> for $name {
+            fn from(value: i32) -> Self {
+                match value {
+                    $($value => $name::$variant,)*
+                    _ => $name::$unknown(value),
+                }
+            }
+        }
+    };
+}
+
 #[cfg(feature = "message")]
 pub mod message;
+#[cfg(feature = "profile")]
+pub mod profile;
 #[cfg(feature = "task")]
 pub mod task;
 
 #[cfg(test)]
 mod test {
-    use crate::task::{AsstTaskParam, StartUpParams};
+    use crate::task::*;
 
     #[test]
     fn test_task_param() {
         let param = AsstTaskParam::StartUp(StartUpParams::default());
         assert_eq!(param.name(), "StartUp")
     }
+
+    #[test]
+    fn test_int_enum_param() {
+        let param = ReclamationAlgorithmParams {
+            mode: ReclamationAlgorithmMode::Craft,
+            ..Default::default()
+        };
+        let json: serde_json::Value = serde_json::from_str(&param.json()).unwrap();
+        assert_eq!(json["mode"], 1);
+        assert_eq!(json["theme"], "Fire");
+
+        let param: ReclamationAlgorithmParams = serde_json::from_str(r#"{"mode":1}"#).unwrap();
+        assert_eq!(param.mode, ReclamationAlgorithmMode::Craft);
+        assert!(serde_json::from_str::<ReclamationAlgorithmParams>(r#"{"mode":2}"#).is_err());
+    }
+
+    #[test]
+    fn test_infrast_mode() {
+        let param = InfrastParams {
+            mode: InfrastMode::Custom {
+                filename: "plan.json".to_string(),
+                plan_index: 2,
+            },
+            ..Default::default()
+        };
+        let json: serde_json::Value = serde_json::from_str(&param.json()).unwrap();
+        assert_eq!(json["mode"], 10000);
+        assert_eq!(json["filename"], "plan.json");
+        assert_eq!(json["plan_index"], 2);
+
+        let param: InfrastParams = serde_json::from_value(json).unwrap();
+        assert!(matches!(
+            param.mode,
+            InfrastMode::Custom { plan_index: 2, .. }
+        ));
+        assert_eq!(InfrastParams::default().mode, InfrastMode::Default);
+        assert!(serde_json::from_str::<InfrastParams>(r#"{"mode":10000}"#).is_err());
+        assert!(serde_json::from_str::<InfrastParams>(r#"{"mode":10000,"filename":"f"}"#).is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut param: RoguelikeParams =
+            serde_json::from_str(r#"{"theme":"Sami","mode":5}"#).unwrap();
+        assert!(param.validate().is_empty());
+
+        param.theme = RoguelikeTheme::Mizuki;
+        let errors = param.validate();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "mode");
+
+        let param: RoguelikeParams =
+            serde_json::from_str(r#"{"theme":"JieGarden","mode":6}"#).unwrap();
+        assert_eq!(param.theme, RoguelikeTheme::JieGarden);
+        let param: RoguelikeParams =
+            serde_json::from_str(r#"{"theme":"NewTheme","mode":5}"#).unwrap();
+        assert_eq!(param.theme, RoguelikeTheme::Unknown("NewTheme".to_string()));
+        assert!(param.validate().is_empty());
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&param.json()).unwrap()["theme"],
+            "NewTheme"
+        );
+
+        let param: RecruitParams =
+            serde_json::from_str(r#"{"select":[4,7],"recruitment_time":{"3":545},"times":-1}"#)
+                .unwrap();
+        let mut fields: Vec<_> = param.validate().into_iter().map(|e| e.field).collect();
+        fields.sort();
+        assert_eq!(fields, ["recruitment_time.3", "select.1", "times"]);
+
+        let param = AsstTaskParam::Infrast(InfrastParams {
+            threshold: 1.5,
+            ..Default::default()
+        });
+        assert_eq!(param.validate()[0].field, "threshold");
+        assert!(AsstTaskParam::Fight(FightParams::default())
+            .validate()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_runtime_patch() {
+        let old = AsstTaskParam::Fight(FightParams::default());
+        let new = AsstTaskParam::Fight(FightParams {
+            medicine: 2,
+            stage: "1-7".parse().unwrap(),
+            ..Default::default()
+        });
+        let patch: serde_json::Value =
+            serde_json::from_str(&old.runtime_patch(&new).unwrap()).unwrap();
+        assert_eq!(patch, serde_json::json!({"medicine": 2, "stage": "1-7"}));
+        assert_eq!(old.runtime_patch(&old).unwrap(), "{}");
+
+        let new = AsstTaskParam::Fight(FightParams {
+            dr_grandet: true,
+            ..Default::default()
+        });
+        assert!(matches!(
+            old.runtime_patch(&new),
+            Err(TaskParamError::NotRuntimeModifiableError(field)) if field == "DrGrandet"
+        ));
+
+        let new = AsstTaskParam::StartUp(StartUpParams::default());
+        assert!(matches!(
+            old.runtime_patch(&new),
+            Err(TaskParamError::TaskTypeMismatchError(..))
+        ));
+    }
+
+    #[test]
+    fn test_infrast_plan() {
+        use crate::task::infrast_plan::InfrastPlanFile;
+
+        let file = InfrastPlanFile::from_json(
+            r#"{
+                "plans": [{
+                    "name": "day",
+                    "period": [["08:00", "24:00"]],
+                    "drones": {"enable": true, "room": "trading", "index": 1, "order": "pre"},
+                    "rooms": {
+                        "control": [{"operators": ["Amiya"]}],
+                        "trading": [{"operators": ["Amiya", "Texas"], "product": "Pure Gold"}]
+                    }
+                }]
+            }"#,
+        )
+        .unwrap();
+        let fields: Vec<_> = file.validate().into_iter().map(|e| e.field).collect();
+        assert_eq!(
+            fields,
+            [
+                "plans.0.period.0",
+                "plans.0.rooms.trading.0.operators",
+                "plans.0.rooms.trading.0.product"
+            ]
+        );
+
+        let json = file.to_json().unwrap();
+        let back = InfrastPlanFile::from_json(&json).unwrap();
+        assert_eq!(back.to_json().unwrap(), json);
+    }
+
+    #[test]
+    fn test_copilot_file() {
+        use crate::task::copilot::{CopilotAction, CopilotActionKind, CopilotFile, Direction};
+
+        let file = CopilotFile::from_json(
+            r#"{
+                "stage_name": "1-7",
+                "opers": [{"name": "Exusiai", "skill": 4}],
+                "groups": [{"name": "Guard", "opers": [{"name": "SilverAsh", "skill": 3}]}],
+                "actions": [
+                    {"type": "部署", "name": "Guard", "location": [1, 2], "direction": "左"},
+                    {"type": "Deploy", "name": "Amiya", "location": [3, 4], "kills": 5},
+                    {"type": "Skill"},
+                    {"type": "MoveCamera", "distance": [1.5, 0]}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            file.actions[0].kind,
+            CopilotActionKind::Deploy {
+                direction: Direction::Left,
+                ..
+            }
+        ));
+        let fields: Vec<_> = file.validate().into_iter().map(|e| e.field).collect();
+        assert_eq!(fields, ["opers.0.skill", "actions.1.name", "actions.2"]);
+
+        let json: serde_json::Value = serde_json::from_str(&file.to_json().unwrap()).unwrap();
+        assert_eq!(json["actions"][0]["type"], "Deploy");
+        assert_eq!(json["actions"][1]["kills"], 5);
+        let back: CopilotFile = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&back).unwrap(), json);
+
+        let actions: Vec<CopilotAction> = serde_json::from_str(
+            r#"[
+                {"name": "Exusiai", "location": [5, 3]},
+                {"type": "deploy", "name": "Exusiai", "location": [5, 3]},
+                {"type": "SPEEDUP", "kills": 1}
+            ]"#,
+        )
+        .unwrap();
+        assert!(matches!(actions[0].kind, CopilotActionKind::Deploy { .. }));
+        assert!(matches!(actions[1].kind, CopilotActionKind::Deploy { .. }));
+        assert!(matches!(actions[2].kind, CopilotActionKind::SpeedUp));
+        assert_eq!(actions[2].kills, Some(1));
+        assert!(serde_json::from_str::<CopilotAction>(r#"{"type": "Jump"}"#).is_err());
+
+        let file = CopilotFile::from_json(
+            r#"{
+                "stage_name": "1-7",
+                "actions": [
+                    {"type": "SkillUsage", "skill_usage": 2},
+                    {"type": "DrawCard"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let fields: Vec<_> = file.validate().into_iter().map(|e| e.field).collect();
+        assert_eq!(
+            fields,
+            ["actions.0", "actions.0.skill_times", "actions.1.type"]
+        );
+    }
+
+    #[test]
+    fn test_sss_copilot_file() {
+        use crate::task::sss_copilot::{SSSCopilotFile, SSSStrategy};
+
+        let file = SSSCopilotFile::from_json(
+            r#"{
+                "type": "SSS",
+                "stage_name": "Dossoles",
+                "strategy": "自由策略",
+                "opers": [{"name": "Thorns", "skill": 3}],
+                "stages": [{
+                    "stage_name": "Gate",
+                    "strategies": [{"location": [1, 2]}],
+                    "actions": [{"type": "DrawCard"}, {"type": "检查重开", "name": "Thorns"}]
+                }]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(file.strategy, Some(SSSStrategy::Free));
+        let fields: Vec<_> = file.validate().into_iter().map(|e| e.field).collect();
+        assert_eq!(fields, ["stages.0.strategies.0"]);
+
+        let json: serde_json::Value = serde_json::from_str(&file.to_json().unwrap()).unwrap();
+        assert_eq!(json["stages"][0]["actions"][1]["type"], "CheckIfStartOver");
+        let back: SSSCopilotFile = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&back).unwrap(), json);
+        assert!(SSSCopilotFile::from_json(r#"{"type": "Copilot", "stage_name": ""}"#).is_err());
+    }
+
+    #[test]
+    fn test_stage_code() {
+        for (code, kind) in [
+            ("", StageCode::Current),
+            ("Last", StageCode::Last),
+            ("1-7", StageCode::MainStory("1-7".to_string())),
+            ("S4-1", StageCode::MainStory("S4-1".to_string())),
+            ("10-1-HARD", StageCode::MainStory("10-1-HARD".to_string())),
+            ("R8-11", StageCode::MainStory("R8-11".to_string())),
+            ("JT8-2", StageCode::MainStory("JT8-2".to_string())),
+            ("M8-8", StageCode::MainStory("M8-8".to_string())),
+            ("LS-6", StageCode::Resource("LS-6".to_string())),
+            ("PR-A-2", StageCode::Resource("PR-A-2".to_string())),
+            ("Annihilation", StageCode::Annihilation(None)),
+            (
+                "Chernobog@Annihilation",
+                StageCode::Annihilation(Some("Chernobog".to_string())),
+            ),
+            ("IC-9", StageCode::Event("IC-9".to_string())),
+            ("SV-EX-8", StageCode::Event("SV-EX-8".to_string())),
+        ] {
+            let stage: StageCode = code.parse().unwrap();
+            assert_eq!(stage, kind);
+            assert_eq!(serde_json::to_value(&stage).unwrap(), code);
+        }
+
+        for code in ["1-", "CE-7", "PR-E-1", "ce-6", "1-777", "@Annihilation"] {
+            assert!(code.parse::<StageCode>().is_err(), "{}", code);
+        }
+        assert!(serde_json::from_str::<FightParams>(r#"{"stage":"CE-0"}"#).is_err());
+    }
+
+    #[test]
+    fn test_task_param_round_trip() {
+        let params = vec![
+            AsstTaskParam::StartUp(StartUpParams::default()),
+            AsstTaskParam::CloseDown(CloseDownParams::default()),
+            AsstTaskParam::Fight(FightParams::default()),
+            AsstTaskParam::Recruit(RecruitParams::default()),
+            AsstTaskParam::Infrast(InfrastParams::default()),
+            AsstTaskParam::Mall(MallParams::default()),
+            AsstTaskParam::Award(AwardParams::default()),
+            AsstTaskParam::Roguelike(RoguelikeParams::default()),
+            AsstTaskParam::Copilot(CopilotParams::default()),
+            AsstTaskParam::SSSCopilot(SSSCopilotParams::default()),
+            AsstTaskParam::Depot(DepotParams::default()),
+            AsstTaskParam::OperBox(OperBoxParams::default()),
+            AsstTaskParam::ReclamationAlgorithm(ReclamationAlgorithmParams::default()),
+            AsstTaskParam::Custom(CustomParams::default()),
+            AsstTaskParam::SingleStep(SingleStepParams::default()),
+            AsstTaskParam::VideoRecognition(VideoRecognitionParams::default()),
+            AsstTaskParam::Debug(DebugParams::default()),
+        ];
+
+        for param in params {
+            let json: serde_json::Value = serde_json::from_str(&param.param()).unwrap();
+
+            let tagged = serde_json::to_value(&param).unwrap();
+            assert_eq!(tagged["type"], param.name());
+            assert_eq!(tagged["params"], json);
+            let back: AsstTaskParam = serde_json::from_value(tagged).unwrap();
+            assert_eq!(back.name(), param.name());
+            assert_eq!(
+                serde_json::from_str::<serde_json::Value>(&back.param()).unwrap(),
+                json
+            );
+
+            let back = AsstTaskParam::from_name_and_json(&param.name(), &param.param()).unwrap();
+            assert_eq!(back.name(), param.name());
+            assert_eq!(
+                serde_json::from_str::<serde_json::Value>(&back.param()).unwrap(),
+                json
+            );
+        }
+
+        assert!(matches!(
+            AsstTaskParam::from_name_and_json("Unknown", "{}"),
+            Err(TaskParamError::UnknownTaskNameError(_))
+        ));
+    }
+
+    #[cfg(feature = "profile")]
+    #[test]
+    fn test_task_profile() {
+        use crate::profile::{ProfileError, ProfileTask, TaskProfile};
+
+        let profile = TaskProfile {
+            name: "daily".to_string(),
+            tasks: vec![
+                ProfileTask {
+                    name: "wake up".to_string(),
+                    task: AsstTaskParam::StartUp(StartUpParams::default()),
+                },
+                ProfileTask {
+                    name: "farm".to_string(),
+                    task: AsstTaskParam::Fight(FightParams {
+                        stage: "1-7".parse().unwrap(),
+                        ..Default::default()
+                    }),
+                },
+            ],
+        };
+
+        for loaded in [
+            TaskProfile::from_json(&profile.to_json().unwrap()).unwrap(),
+            TaskProfile::from_toml(&profile.to_toml().unwrap()).unwrap(),
+        ] {
+            assert_eq!(loaded.name, "daily");
+            let names: Vec<_> = loaded.tasks.iter().map(|t| t.task.name()).collect();
+            assert_eq!(names, ["StartUp", "Fight"]);
+            assert_eq!(loaded.tasks[1].task.param(), profile.tasks[1].task.param());
+        }
+
+        let err = TaskProfile::from_json(
+            r#"{"tasks": [{"name": "a", "type": "StartUp"}, {"name": "b", "type": "Fight", "params": {"medicine": "x"}}]}"#,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ProfileError::InvalidTaskError { index: 1, .. }
+        ));
+
+        let profile = TaskProfile {
+            name: "step".to_string(),
+            tasks: vec![ProfileTask {
+                name: "step".to_string(),
+                task: AsstTaskParam::SingleStep(SingleStepParams {
+                    details: serde_json::json!({"a": [1, null]}),
+                    ..Default::default()
+                }),
+            }],
+        };
+        assert!(matches!(
+            profile.to_toml(),
+            Err(ProfileError::TomlNullError(path)) if path == "tasks.0.params.details.a.1"
+        ));
+
+        let mut profile = profile;
+        if let AsstTaskParam::SingleStep(params) = &mut profile.tasks[0].task {
+            params.details = serde_json::json!({"a": null});
+        }
+        assert!(matches!(
+            profile.to_toml(),
+            Err(ProfileError::TomlNullError(path)) if path == "tasks.0.params.details.a"
+        ));
+
+        let err = TaskProfile::from_json(
+            r#"{"tasks": [{"name": "a", "type": "StartUp"}, {"name": "b"}]}"#,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ProfileError::InvalidTaskError {
+                index: 1,
+                source: TaskParamError::MissingTaskTypeError,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "message")]
+    fn test_message_parse_errors() {
+        use crate::message::{AsstMessage, MessageParseError};
+
+        let subtask = r#"{"details": {}}"#;
+        assert!(matches!(
+            AsstMessage::get(20001, subtask),
+            Err(MessageParseError::JsonParseError(_))
+        ));
+        let subtask = r#"{"taskchain": "Fight", "class": "asst::NewSubTask", "subtask": "NewSubTask", "taskid": 1, "details": {}}"#;
+        assert!(matches!(
+            AsstMessage::get(20001, subtask),
+            Err(MessageParseError::JsonParseError(_))
+        ));
+        assert!(matches!(
+            AsstMessage::get(10001, r#"{"taskchain": "Fight"}"#),
+            Err(MessageParseError::JsonParseError(_))
+        ));
+        assert!(AsstMessage::get(20003, r#"{"what": "StageDrops"}"#).is_err());
+        let drops = r#"{"taskchain": "Fight", "class": "c", "uuid": "u", "what": "StageDrops", "details": {"stars": 3}}"#;
+        match AsstMessage::get(20003, drops) {
+            Err(MessageParseError::JsonParseError(err)) => {
+                assert!(err.to_string().contains("missing field `stage`"), "{}", err);
+            }
+            _ => panic!("Expected JsonParseError"),
+        }
+        let completed = r#"{"taskchain": "Recruit", "class": "c", "uuid": "u", "what": "RecruitSlotCompleted", "details": {}}"#;
+        assert!(matches!(
+            AsstMessage::get(20003, completed),
+            Ok(AsstMessage::SubTaskExtraInfo(_))
+        ));
+        assert!(AsstMessage::get(10002, "not json").is_err());
+
+        let taskchain = r#"{"taskchain": "Fight", "uuid": "u", "taskid": 1}"#;
+        assert!(matches!(
+            AsstMessage::get(10001, taskchain),
+            Ok(AsstMessage::TaskChainInfo(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "message")]
+    fn test_message_unknown_variants() {
+        use crate::message::detail::subtask::*;
+        use crate::message::detail::taskchain::TaskChain;
+        use crate::message::AsstMessage;
+
+        let taskchain = r#"{"taskchain": "NewChain", "uuid": "u", "taskid": 1}"#;
+        match AsstMessage::get(10001, taskchain).unwrap() {
+            AsstMessage::TaskChainInfo(detail) => {
+                assert!(
+                    matches!(&detail.taskchain, TaskChain::Unknown(name) if name == "NewChain")
+                );
+                assert_eq!(detail.taskchain.to_string(), "NewChain");
+            }
+            _ => panic!("Expected TaskChainInfo"),
+        }
+
+        let extra = r#"{"taskchain": "Fight", "class": "c", "uuid": "u", "what": "NewInfo", "details": {"a": 1}}"#;
+        match AsstMessage::get(20003, extra).unwrap() {
+            AsstMessage::SubTaskExtraInfo(detail) => match detail.details {
+                SubTaskExtraInfoDetails::Unknown { what, details } => {
+                    assert_eq!(what, "NewInfo");
+                    assert_eq!(details["a"], 1);
+                }
+                _ => panic!("Expected Unknown"),
+            },
+            _ => panic!("Expected SubTaskExtraInfo"),
+        }
+
+        let investment = r#"{"taskchain": "Roguelike", "class": "c", "uuid": "u", "what": "RoguelikeInvestment", "details": {"count": 13, "total": 26, "deposit": 100}}"#;
+        assert!(matches!(
+            AsstMessage::get(20003, investment),
+            Ok(AsstMessage::SubTaskExtraInfo(SubTaskExtraInfoDetail {
+                details: SubTaskExtraInfoDetails::RoguelikeInvestment(_),
+                ..
+            }))
+        ));
+
+        let enter = r#"{"taskchain": "Roguelike", "class": "asst::ProcessTask", "subtask": "ProcessTask", "taskid": 1, "uuid": "u", "details": {"task": "StageTraderEnter", "action": 512, "exec_times": 1, "max_times": 1, "algorithm": 1}}"#;
+        match AsstMessage::get(20001, enter).unwrap() {
+            AsstMessage::SubTaskInfo(SubTaskDetail::ProcessTask { details, .. }) => {
+                assert!(details.task.is_roguelike_stage_enter());
+            }
+            _ => panic!("Expected ProcessTask"),
+        }
+        assert!(!Task::RoguelikeLastReward.is_roguelike_stage_enter());
+
+        let report = r#"{"taskchain": "Fight", "class": "asst::ReportDataTask", "subtask": "ReportDataTask", "taskid": 1, "uuid": "u", "details": {"why": "Failed"}}"#;
+        match AsstMessage::get(20000, report).unwrap() {
+            AsstMessage::SubTaskInfo(SubTaskDetail::ReportDataTask { details, .. }) => {
+                assert_eq!(details.unwrap().why.as_deref(), Some("Failed"));
+            }
+            _ => panic!("Expected ReportDataTask"),
+        }
+        let report = r#"{"taskchain": "Fight", "class": "asst::ReportDataTask", "subtask": "ReportDataTask", "taskid": 1, "uuid": "u", "details": {"why": 1}}"#;
+        assert!(AsstMessage::get(20000, report).is_err());
+
+        let subtask = r#"{"taskchain": "Fight", "class": "asst::ProcessTask", "subtask": "ProcessTask", "taskid": 1, "uuid": "u", "details": {"task": "NewTask", "action": 0, "exec_times": 1, "max_times": 1, "algorithm": 0}}"#;
+        match AsstMessage::get(20001, subtask).unwrap() {
+            AsstMessage::SubTaskInfo(SubTaskDetail::ProcessTask { details, .. }) => {
+                assert!(matches!(details.task, Task::Unknown(name) if name == "NewTask"));
+                assert_eq!(details.action, ProcessTaskAction::Invalid);
+                assert_eq!(details.algorithm, AlgorithmType::JustReturn);
+            }
+            _ => panic!("Expected SubTaskInfo"),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "message")]
+    fn test_connection_info() {
+        use crate::message::detail::{ConnectionInfoDetails, ConnectionInfoWhat};
+        use crate::message::AsstMessage;
+
+        let uuid_got = r#"{"what": "UuidGot", "why": "", "uuid": "", "details": {"adb": "adb", "address": "127.0.0.1:5555", "config": "General", "uuid": "device"}}"#;
+        match AsstMessage::get(2, uuid_got).unwrap() {
+            AsstMessage::ConnectionInfo(detail) => {
+                assert_eq!(detail.details.what(), ConnectionInfoWhat::UuidGot);
+                match detail.details {
+                    ConnectionInfoDetails::UuidGot(details) => {
+                        assert_eq!(details.uuid, "device");
+                        assert_eq!(details.adb.address, "127.0.0.1:5555");
+                    }
+                    _ => panic!("Expected UuidGot"),
+                }
+            }
+            _ => panic!("Expected ConnectionInfo"),
+        }
+
+        let connected = r#"{"what": "Connected", "why": "", "uuid": "u", "details": {}}"#;
+        assert!(AsstMessage::get(2, connected).is_err());
+        let uuid_got = r#"{"what": "UuidGot", "why": "", "uuid": "", "details": {"adb": "adb", "address": "a", "config": "c"}}"#;
+        let err = AsstMessage::get(2, uuid_got).unwrap_err();
+        assert!(err.to_string().contains("missing field `uuid`"), "{}", err);
+    }
+
+    #[test]
+    #[cfg(feature = "message")]
+    fn test_message_round_trip() {
+        use crate::message::AsstMessage;
+
+        let messages = [
+            (0, "{}"),
+            (5, "{}"),
+            (
+                2,
+                r#"{"what": "Connected", "why": "", "uuid": "u", "details": {"adb": "adb", "address": "127.0.0.1:5555", "config": "General"}}"#,
+            ),
+            (
+                2,
+                r#"{"what": "UnsupportedResolution", "why": "Too low", "uuid": "u", "details": {"adb": "adb", "address": "127.0.0.1:5555", "config": "General", "width": 800, "height": 600}}"#,
+            ),
+            (
+                2,
+                r#"{"what": "Reconnecting", "why": "", "uuid": "u", "details": {"reconnect": "adb connect", "times": 1}}"#,
+            ),
+            (
+                2,
+                r#"{"what": "NewConnectionInfo", "why": "", "uuid": "u", "details": {"a": 1}}"#,
+            ),
+            (3, r#"{"chain": "Fight", "uuid": "u", "tasks": [1, 2]}"#),
+            (10002, r#"{"taskchain": "Fight", "uuid": "u", "taskid": 1}"#),
+            (
+                10003,
+                r#"{"what": "RoutingRestart", "why": "TooManyBattlesAhead"}"#,
+            ),
+            (
+                20001,
+                r#"{"taskchain": "Fight", "class": "asst::ProcessTask", "subtask": "ProcessTask", "taskid": 1, "uuid": "u", "details": {"task": "StartButton2", "action": 512, "exec_times": 1, "max_times": 999, "algorithm": 0}}"#,
+            ),
+            (
+                20002,
+                r#"{"taskchain": "Fight", "class": "asst::ReportDataTask", "subtask": "ReportDataTask", "taskid": 1, "uuid": "u", "details": {"why": "Failed"}}"#,
+            ),
+            (
+                20004,
+                r#"{"taskchain": "Fight", "class": "asst::NewSubTask", "subtask": "NewSubTask", "taskid": 1, "uuid": "u", "details": {"a": 1}}"#,
+            ),
+            (
+                20002,
+                r#"{"taskchain": "Fight", "class": "asst::ProcessTask", "subtask": "ProcessTask", "taskid": 1, "uuid": "u", "details": {"task": "Roguelike@LastReward", "action": 123, "exec_times": 1, "max_times": 1, "algorithm": 2}}"#,
+            ),
+            (
+                20001,
+                r#"{"taskchain": "Fight", "class": "asst::StageNavigationTask", "subtask": "StageNavigationTask", "taskid": 1, "uuid": "u", "details": {}}"#,
+            ),
+            (
+                20001,
+                r#"{"taskchain": "Copilot", "class": "asst::CombatRecordRecognitionTask", "subtask": "CombatRecordRecognitionTask", "taskid": 1, "uuid": "u", "details": {"what": "OCRing"}}"#,
+            ),
+            (
+                20003,
+                r#"{"taskchain": "Fight", "class": "asst::StageDropsTaskPlugin", "uuid": "u", "what": "StageDrops", "details": {"stage": {"stageCode": "1-7", "stageId": "main_01-07"}, "stars": 3, "stats": [{"itemId": "2001", "itemName": "Drill Battle Record", "quantity": 10, "addQuantity": 2}]}}"#,
+            ),
+            (
+                20003,
+                r#"{"taskchain": "Recruit", "class": "c", "uuid": "u", "what": "RecruitSpecialTag", "details": {"tag": "Top Operator"}}"#,
+            ),
+            (
+                20003,
+                r#"{"taskchain": "Roguelike", "class": "c", "uuid": "u", "what": "RoguelikeInvestment", "details": {"count": 13, "total": 26, "deposit": 100}}"#,
+            ),
+            (
+                20003,
+                r#"{"taskchain": "Roguelike", "class": "c", "uuid": "u", "what": "RoguelikeSettlement", "details": {"game_pass": false, "floor": 3, "step": 2, "combat": 6, "emergency": 1, "boss": 0, "recruit": 8, "collection": 5, "difficulty": "0", "score": 210, "exp": 1800, "skill": 20}}"#,
+            ),
+            (
+                20003,
+                r#"{"taskchain": "Roguelike", "class": "c", "uuid": "u", "what": "RoguelikeCollapsalParadigms", "details": {"deepen_or_weaken": 1, "cur": "a", "prev": ""}}"#,
+            ),
+            (
+                20003,
+                r#"{"taskchain": "Fight", "class": "c", "uuid": "u", "what": "NewInfo", "details": {"a": [1]}}"#,
+            ),
+        ];
+
+        for (code, details) in messages {
+            let message = AsstMessage::get(code, details).unwrap();
+            assert_eq!(i32::from(message.code()), code);
+            let json = serde_json::to_value(&message).unwrap();
+            assert_eq!(
+                json,
+                serde_json::from_str::<serde_json::Value>(details).unwrap()
+            );
+
+            let replayed = AsstMessage::get(message.code().into(), &json.to_string()).unwrap();
+            assert_eq!(serde_json::to_value(&replayed).unwrap(), json);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "message")]
+    fn test_message_envelope() {
+        use crate::message::{AsstMessage, AsstMessageEnvelope, MessageParseError};
+
+        let envelope =
+            AsstMessageEnvelope::parse(3, r#"{"chain": "Fight", "uuid": "u", "tasks": []}"#)
+                .unwrap();
+        assert_eq!(envelope.code, 3);
+        assert!(matches!(
+            envelope.message,
+            AsstMessage::AllTasksCompleted(_)
+        ));
+
+        let err = AsstMessageEnvelope::parse(10001, "not json").unwrap_err();
+        assert_eq!(err.code, 10001);
+        assert_eq!(err.raw, "not json");
+        assert!(err.sequence > envelope.sequence);
+        assert!(err.received_at >= envelope.received_at);
+        assert!(matches!(err.source, MessageParseError::JsonParseError(_)));
+    }
+
+    #[test]
+    #[cfg(feature = "message")]
+    fn test_message_code() {
+        use crate::message::detail::taskchain::TaskChainStatus;
+        use crate::message::{AsstMessage, AsstMsgCode, MessageParseError};
+
+        for code in [
+            0, 1, 2, 3, 4, 5, 10000, 10001, 10002, 10003, 10004, 20000, 20003, 20004,
+        ] {
+            assert_eq!(i32::from(AsstMsgCode::try_from(code).unwrap()), code);
+        }
+        assert!(matches!(
+            AsstMsgCode::try_from(6),
+            Err(MessageParseError::UnknownMessageCodeError(6))
+        ));
+        assert_eq!(AsstMsgCode::Destroyed.to_string(), "Destroyed");
+        assert!(matches!(
+            AsstMessage::get(5, "{}").unwrap(),
+            AsstMessage::Destroyed
+        ));
+        assert!(matches!(
+            TaskChainStatus::try_from(AsstMsgCode::SubTaskStart),
+            Err(MessageParseError::UnknownTaskChainStatusError(20001))
+        ));
+    }
 }
@@ -1,27 +1,68 @@
 pub mod subtask;
 pub mod taskchain;
 
-use serde::Deserialize;
+use serde::de::Error;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 
-#[derive(Deserialize, Debug)]
+/// How the `Unknown` fallback of a `what`-keyed details enum is serialized
+#[derive(Serialize)]
+struct UnknownWhat<'a> {
+    what: &'a str,
+    #[serde(skip_serializing_if = "Value::is_null")]
+    details: &'a Value,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
 pub struct InitFailedDetail {
     pub what: String,
     pub why: String,
     pub details: String,
 }
 
-#[derive(Deserialize, Debug)]
-pub struct ConnectionInfoDetails {
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AdbDetails {
     pub adb: String,
     pub address: String,
     pub config: String,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ResolutionDetails {
+    #[serde(flatten)]
+    pub adb: AdbDetails,
+    pub width: i32,
+    pub height: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UuidGotDetails {
+    #[serde(flatten)]
+    pub adb: AdbDetails,
+    pub uuid: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReconnectDetails {
+    /// The command used to reconnect
+    pub reconnect: String,
+    #[serde(default)]
+    pub times: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FastestWayToScreencapDetails {
+    /// Time in milliseconds a screencap takes
+    pub cost: i64,
+    pub method: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub enum ConnectionInfoWhat {
     ConnectFailed,
     Connected,
     UuidGot,
+    ResolutionGot,
     UnsupportedResolution,
     ResolutionError,
     Reconnecting,
@@ -29,30 +70,144 @@ pub enum ConnectionInfoWhat {
     Disconnect,
     ScreencapFailed,
     TouchModeNotAvailable,
+    FastestWayToScreencap,
+    /// A connection event unknown to this version, with its original name
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+/// Details of a `ConnectionInfo` message, keyed by [ConnectionInfoWhat](ConnectionInfoWhat)
+///
+/// Only a `what` unknown to this version falls back to `Unknown`, malformed details of a known one are an error
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(remote = "Self")]
+#[serde(tag = "what", content = "details")]
+pub enum ConnectionInfoDetails {
+    ConnectFailed(AdbDetails),
+    Connected(AdbDetails),
+    UuidGot(UuidGotDetails),
+    ResolutionGot(ResolutionDetails),
+    UnsupportedResolution(ResolutionDetails),
+    ResolutionError(AdbDetails),
+    Reconnecting(ReconnectDetails),
+    Reconnected(ReconnectDetails),
+    Disconnect(ReconnectDetails),
+    ScreencapFailed(AdbDetails),
+    TouchModeNotAvailable(AdbDetails),
+    FastestWayToScreencap(FastestWayToScreencapDetails),
+    /// A connection event unknown to this version, with its original `what` and raw `details`
+    #[serde(skip)]
+    Unknown {
+        what: String,
+        #[serde(default)]
+        details: Value,
+    },
 }
 
-#[derive(Deserialize, Debug)]
+impl ConnectionInfoDetails {
+    pub fn what(&self) -> ConnectionInfoWhat {
+        match self {
+            ConnectionInfoDetails::ConnectFailed(_) => ConnectionInfoWhat::ConnectFailed,
+            ConnectionInfoDetails::Connected(_) => ConnectionInfoWhat::Connected,
+            ConnectionInfoDetails::UuidGot(_) => ConnectionInfoWhat::UuidGot,
+            ConnectionInfoDetails::ResolutionGot(_) => ConnectionInfoWhat::ResolutionGot,
+            ConnectionInfoDetails::UnsupportedResolution(_) => {
+                ConnectionInfoWhat::UnsupportedResolution
+            }
+            ConnectionInfoDetails::ResolutionError(_) => ConnectionInfoWhat::ResolutionError,
+            ConnectionInfoDetails::Reconnecting(_) => ConnectionInfoWhat::Reconnecting,
+            ConnectionInfoDetails::Reconnected(_) => ConnectionInfoWhat::Reconnected,
+            ConnectionInfoDetails::Disconnect(_) => ConnectionInfoWhat::Disconnect,
+            ConnectionInfoDetails::ScreencapFailed(_) => ConnectionInfoWhat::ScreencapFailed,
+            ConnectionInfoDetails::TouchModeNotAvailable(_) => {
+                ConnectionInfoWhat::TouchModeNotAvailable
+            }
+            ConnectionInfoDetails::FastestWayToScreencap(_) => {
+                ConnectionInfoWhat::FastestWayToScreencap
+            }
+            ConnectionInfoDetails::Unknown { what, .. } => {
+                ConnectionInfoWhat::Unknown(what.clone())
+            }
+        }
+    }
+}
+
+impl Serialize for ConnectionInfoDetails {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            ConnectionInfoDetails::Unknown { what, details } => {
+                UnknownWhat { what, details }.serialize(serializer)
+            }
+            _ => ConnectionInfoDetails::serialize(self, serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ConnectionInfoDetails {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let mut value: Value = Deserialize::deserialize(deserializer)?;
+        let what: ConnectionInfoWhat =
+            serde_json::from_value(value["what"].clone()).map_err(D::Error::custom)?;
+        match what {
+            ConnectionInfoWhat::Unknown(what) => {
+                let details = value["details"].take();
+                Ok(ConnectionInfoDetails::Unknown { what, details })
+            }
+            _ => ConnectionInfoDetails::deserialize(value).map_err(D::Error::custom),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
 pub struct ConnectionInfoDetail {
-    pub what: ConnectionInfoWhat,
     pub why: String,
     pub uuid: String,
+    #[serde(flatten)]
     pub details: ConnectionInfoDetails,
 }
 
-#[derive(Deserialize, Debug)]
+impl<'de> Deserialize<'de> for ConnectionInfoDetail {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value: Value = Deserialize::deserialize(deserializer)?;
+        let why: String = serde_json::from_value(value["why"].clone()).map_err(D::Error::custom)?;
+        let uuid: String =
+            serde_json::from_value(value["uuid"].clone()).map_err(D::Error::custom)?;
+        let what: String =
+            serde_json::from_value(value["what"].clone()).map_err(D::Error::custom)?;
+        let details: Value = value["details"].clone();
+        let details_json = json!({
+            "what":what,
+            "details":details,
+        });
+        let details: ConnectionInfoDetails =
+            serde_json::from_value(details_json).map_err(D::Error::custom)?;
+        Ok(ConnectionInfoDetail { why, uuid, details })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
 pub struct AllTasksCompletedDetail {
     pub chain: taskchain::TaskChain,
     pub uuid: String,
     pub tasks: Vec<i32>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct AsyncCallInfoDetails {
     pub ret: bool,
     pub cost: i64,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct AsyncCallInfoDetail {
     pub uuid: String,
     pub what: String,
@@ -1,27 +1,44 @@
 use std::collections::HashMap;
 
-use serde::Deserialize;
+use serde::de::Error;
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
 use super::taskchain::TaskChain;
+use super::UnknownWhat;
+use crate::message::{AsstMsgCode, MessageParseError};
 
-#[derive(Deserialize, Debug)]
+/// Names of the tasks MaaCore reports in `ProcessTask` details
+#[derive(Serialize, Deserialize, Debug)]
 pub enum Task {
+    StartButton1,
     StartButton2,
     AutoRecruitTask,
     RecognizeDrops,
     CheckStageValid,
+    AnnihilationConfirm,
     MedicineConfirm,
+    ExpiringMedicineConfirm,
     StoneConfirm,
+    AbandonAction,
+    FightMissionFailedAndStop,
+    EndOfAction,
+    OfflineConfirm,
+    GamePass,
     RecruitRefreshConfirm,
     RecruitConfirm,
     RecruitNowConfirm,
     ReportToPenguinStats,
     ReportToYituliu,
     InfrastDormDoubleConfirmButton,
+    InfrastEnteredFlag,
+    ClueConfirm,
+    MallPurchase,
+    AwardBegin,
     StartExplore,
     StageTraderInvestConfirm,
     StageTraderInvestSystemFull,
+    StageTraderRefreshWithDice,
     ExitThenAbandon,
     MissionCompletedFlag,
     MissionFailedFlag,
@@ -29,21 +46,94 @@ pub enum Task {
     StageSafeHouseEnter,
     StageEncounterEnter,
     StageCambatDpsEnter,
+    StageCombatDpsEnter,
     StageEmergencyDps,
     StageDreadfulFoe,
+    #[serde(rename = "StageDreadfulFoe-5Enter")]
+    StageDreadfulFoe5Enter,
+    StageDeepExplorationEnter,
+    #[serde(rename = "Roguelike@LastReward")]
+    RoguelikeLastReward,
+    #[serde(rename = "Roguelike@LastReward2")]
+    RoguelikeLastReward2,
+    #[serde(rename = "Roguelike@LastReward3")]
+    RoguelikeLastReward3,
+    #[serde(rename = "Roguelike@LastReward4")]
+    RoguelikeLastReward4,
+    #[serde(rename = "Roguelike@LastRewardRand")]
+    RoguelikeLastRewardRand,
+    #[serde(rename = "Mizuki@Roguelike@LastReward5")]
+    MizukiRoguelikeLastReward5,
+    #[serde(rename = "Mizuki@Roguelike@LastReward6")]
+    MizukiRoguelikeLastReward6,
     StartGameTask,
+    BattleStartAll,
+    StartToWakeUp,
+    ReturnTo,
+    CloseDown,
+    /// A task unknown to this version, with its original name
+    #[serde(untagged)]
+    Unknown(String),
 }
 
-#[derive(Deserialize, Debug)]
+impl Task {
+    /// Whether the task enters a node of an Integrated Strategies map, MaaCore
+    /// reports entering a stage with the `ProcessTask` of these tasks rather than an extra info
+    pub fn is_roguelike_stage_enter(&self) -> bool {
+        matches!(
+            self,
+            Task::StageTraderEnter
+                | Task::StageSafeHouseEnter
+                | Task::StageEncounterEnter
+                | Task::StageCambatDpsEnter
+                | Task::StageCombatDpsEnter
+                | Task::StageEmergencyDps
+                | Task::StageDreadfulFoe
+                | Task::StageDreadfulFoe5Enter
+                | Task::StageDeepExplorationEnter
+        )
+    }
+}
+
+int_enum! {
+    /// Action a `ProcessTask` performs when its template is matched
+    ProcessTaskAction {
+        Invalid = 0,
+        ClickSelf = 0x101,
+        ClickRect = 0x102,
+        ClickRand = 0x104,
+        DoNothing = 0x200,
+        Stop = 0x400,
+        Swipe = 0x1001,
+        SlowlySwipeToTheLeft = 0x1002,
+        SlowlySwipeToTheRight = 0x1004,
+        Input = 0x2000,
+        _ => Unknown,
+    }
+}
+
+int_enum! {
+    /// Algorithm a `ProcessTask` recognizes its target with
+    AlgorithmType {
+        Invalid = -1,
+        JustReturn = 0,
+        MatchTemplate = 1,
+        OcrDetect = 2,
+        Hash = 3,
+        _ => Unknown,
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
 pub struct ProcessTaskDetails {
     pub task: Task,
-    pub action: i32,
+    pub action: ProcessTaskAction,
     pub exec_times: i32,
     pub max_times: i32,
-    pub algorithm: i32,
+    pub algorithm: AlgorithmType,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum SubTaskStatus {
     SubTaskError,
     SubTaskStart,
@@ -51,50 +141,202 @@ pub enum SubTaskStatus {
     SubTaskStopped,
 }
 
-impl From<i32> for SubTaskStatus {
-    fn from(value: i32) -> Self {
+impl TryFrom<AsstMsgCode> for SubTaskStatus {
+    type Error = MessageParseError;
+
+    fn try_from(value: AsstMsgCode) -> Result<Self, Self::Error> {
         match value {
-            20000 => SubTaskStatus::SubTaskError,
-            20001 => SubTaskStatus::SubTaskStart,
-            20002 => SubTaskStatus::SubTaskCompleted,
-            20004 => SubTaskStatus::SubTaskStopped,
-            _ => panic!("Unknown SubTaskStatus: {}", value),
+            AsstMsgCode::SubTaskError => Ok(SubTaskStatus::SubTaskError),
+            AsstMsgCode::SubTaskStart => Ok(SubTaskStatus::SubTaskStart),
+            AsstMsgCode::SubTaskCompleted => Ok(SubTaskStatus::SubTaskCompleted),
+            AsstMsgCode::SubTaskStopped => Ok(SubTaskStatus::SubTaskStopped),
+            _ => Err(MessageParseError::UnknownSubTaskStatusError(value.into())),
         }
     }
 }
 
-#[derive(Debug)]
-pub enum SubTaskDetail {
-    ProcessTask {
-        status: SubTaskStatus,
-        details: ProcessTaskDetails,
-    },
+impl TryFrom<i32> for SubTaskStatus {
+    type Error = MessageParseError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        AsstMsgCode::try_from(value)
+            .map_err(|_| MessageParseError::UnknownSubTaskStatusError(value))?
+            .try_into()
+    }
 }
 
-impl SubTaskDetail {
-    pub fn new(msg: i32, details: &str) -> Self {
-        let status = SubTaskStatus::from(msg);
-        let details: Value = serde_json::from_str(details).unwrap();
-        let subtask: String = serde_json::from_value(details["subtask"].clone()).unwrap();
-        match subtask.as_str() {
-            "ProcessTask" => {
-                let details: ProcessTaskDetails =
-                    serde_json::from_value(details["details"].clone()).unwrap();
-                SubTaskDetail::ProcessTask { status, details }
-            }
-            _ => panic!("Unknown SubTaskDetail: {}", subtask),
+impl From<SubTaskStatus> for AsstMsgCode {
+    fn from(value: SubTaskStatus) -> Self {
+        match value {
+            SubTaskStatus::SubTaskError => AsstMsgCode::SubTaskError,
+            SubTaskStatus::SubTaskStart => AsstMsgCode::SubTaskStart,
+            SubTaskStatus::SubTaskCompleted => AsstMsgCode::SubTaskCompleted,
+            SubTaskStatus::SubTaskStopped => AsstMsgCode::SubTaskStopped,
         }
     }
 }
 
-#[derive(Deserialize, Debug)]
+/// Generic details of the subtasks for which MaaCore only documents `why`:
+/// ReportDataTask, StageNavigationTask, AutoRecruitTask, RecruitCalcTask,
+/// BattleFormationTask and BattleProcessTask. Any other field is ignored.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct SubTaskWhyDetails {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub why: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct CombatRecordRecognitionDetails {
+    /// The recognition step being run, for the log
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub what: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub why: Option<String>,
+}
+
+/// The fields every `SubTaskInfo` message carries, parsed before its details
+#[derive(Deserialize)]
+struct SubTaskHeader {
+    subtask: String,
+    taskchain: TaskChain,
+    class: String,
+    taskid: i32,
+    uuid: String,
+    #[serde(default)]
+    details: Value,
+}
+
+macro_rules! sub_task_detail {
+    ($($(#[$meta:meta])* $variant:ident($details:ty),)*) => {
+        /// Details of a `SubTaskInfo` message, keyed by the `subtask` class
+        ///
+        /// `details` is `None` when MaaCore sends none.
+        /// The status is carried by the message code, so it is not serialized
+        #[derive(Serialize, Debug)]
+        #[serde(tag = "subtask")]
+        pub enum SubTaskDetail {
+            ProcessTask {
+                taskchain: TaskChain,
+                class: String,
+                taskid: i32,
+                uuid: String,
+                #[serde(skip)]
+                status: SubTaskStatus,
+                details: ProcessTaskDetails,
+            },
+            $(
+                $(#[$meta])*
+                $variant {
+                    taskchain: TaskChain,
+                    class: String,
+                    taskid: i32,
+                    uuid: String,
+                    #[serde(skip)]
+                    status: SubTaskStatus,
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    details: Option<$details>,
+                },
+            )*
+            /// A subtask unknown to this version, with its original class name
+            #[serde(untagged)]
+            Unknown {
+                subtask: String,
+                taskchain: TaskChain,
+                class: String,
+                taskid: i32,
+                uuid: String,
+                #[serde(skip)]
+                status: SubTaskStatus,
+                #[serde(skip_serializing_if = "Value::is_null")]
+                details: Value,
+            },
+        }
+
+        impl SubTaskDetail {
+            pub fn status(&self) -> SubTaskStatus {
+                match self {
+                    SubTaskDetail::ProcessTask { status, .. } => *status,
+                    $(SubTaskDetail::$variant { status, .. } => *status,)*
+                    SubTaskDetail::Unknown { status, .. } => *status,
+                }
+            }
+
+            pub fn new(msg: AsstMsgCode, details: &str) -> Result<Self, MessageParseError> {
+                let status = SubTaskStatus::try_from(msg)?;
+                let SubTaskHeader {
+                    subtask,
+                    taskchain,
+                    class,
+                    taskid,
+                    uuid,
+                    details,
+                } = serde_json::from_str(details)?;
+                match subtask.as_str() {
+                    "ProcessTask" => {
+                        let details: ProcessTaskDetails = serde_json::from_value(details)?;
+                        Ok(SubTaskDetail::ProcessTask {
+                            taskchain,
+                            class,
+                            taskid,
+                            uuid,
+                            status,
+                            details,
+                        })
+                    }
+                    $(stringify!($variant) => {
+                        let details = serde_json::from_value(details)?;
+                        Ok(SubTaskDetail::$variant {
+                            taskchain,
+                            class,
+                            taskid,
+                            uuid,
+                            status,
+                            details,
+                        })
+                    })*
+                    _ => Ok(SubTaskDetail::Unknown {
+                        subtask,
+                        taskchain,
+                        class,
+                        taskid,
+                        uuid,
+                        status,
+                        details,
+                    }),
+                }
+            }
+        }
+    };
+}
+
+sub_task_detail! {
+    /// Recognizing the combat record of a copilot battle
+    CombatRecordRecognitionTask(CombatRecordRecognitionDetails),
+    /// Reporting drops or recruit results to Penguin Stats or Yituliu
+    ReportDataTask(SubTaskWhyDetails),
+    /// Navigating to the stage to fight
+    StageNavigationTask(SubTaskWhyDetails),
+    /// Recruiting operators
+    AutoRecruitTask(SubTaskWhyDetails),
+    /// Calculating the recruit tags
+    RecruitCalcTask(SubTaskWhyDetails),
+    /// Setting up the formation of a copilot battle
+    BattleFormationTask(SubTaskWhyDetails),
+    /// Running the actions of a copilot battle
+    BattleProcessTask(SubTaskWhyDetails),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct StageDropsStage {
     pub stage_code: String,
     pub stage_id: String,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct StageDropsStat {
     pub item_id: String,
@@ -103,30 +345,30 @@ pub struct StageDropsStat {
     pub add_quantity: i32,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct StageDropsDetail {
     pub stage: StageDropsStage,
     pub stars: i32,
     pub stats: Vec<StageDropsStat>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct RecruitTagsDetectedDetail {
     pub tags: Vec<String>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct RecruitSpecialTagDetail {
     pub tag: String,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct RecruitResultOperator {
     pub name: String,
     pub level: i32,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct RecruitResultItem {
     pub tags: Vec<String>,
     pub level: i32,
@@ -134,14 +376,14 @@ pub struct RecruitResultItem {
     pub operators: Vec<RecruitResultOperator>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct RecruitResultDetail {
     pub tags: Vec<String>,
     pub level: i32,
     pub result: Vec<RecruitResultItem>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct RecruitTagsRefreshedDetail {
     pub count: i32,
     pub refresh_limit: i32,
@@ -149,7 +391,7 @@ pub struct RecruitTagsRefreshedDetail {
 
 pub type RecruitTagsSelectedDetail = RecruitTagsDetectedDetail;
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct EnterFacilityDetail {
     pub facility: String,
     pub index: i32,
@@ -157,57 +399,57 @@ pub struct EnterFacilityDetail {
 
 pub type NotEnoughStaffDetail = EnterFacilityDetail;
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct ProductOfFacilityDetail {
     pub product: String,
     pub facility: String,
     pub index: i32,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct StageInfoDetail {
     pub name: String,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct PenguinIdDetail {
     pub id: String,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DepotItem {
     pub id: String,
     pub have: i32,
     pub name: String,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DepotArkPlannerObject {
     pub items: Vec<DepotItem>,
     #[serde(rename = "@type")]
     pub object_type: String,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DepotArkPlanner {
     pub object: DepotArkPlannerObject,
     pub data: String,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DepotLolicon {
     pub object: HashMap<String, i32>,
     pub data: String,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DepotDetail {
     pub done: bool,
     pub arkplanner: DepotArkPlanner,
     pub lolicon: DepotLolicon,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct OperatorBoxAllItem {
     pub id: String,
     pub name: String,
@@ -215,7 +457,7 @@ pub struct OperatorBoxAllItem {
     pub rarity: i32,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct OperatorBoxOwnItem {
     pub id: String,
     pub name: String,
@@ -226,14 +468,87 @@ pub struct OperatorBoxOwnItem {
     pub rarity: i32,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct OperBoxDetail {
     pub done: bool,
     pub all_oper: Vec<OperatorBoxAllItem>,
     pub own_opes: Vec<OperatorBoxOwnItem>,
 }
 
-#[derive(Deserialize, Debug)]
+/// Originium ingots invested at a roguelike trader
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RoguelikeInvestmentDetail {
+    /// Originium ingots invested this time
+    pub count: i32,
+    /// Originium ingots invested in this run
+    pub total: i32,
+    /// Originium ingots in the deposit
+    pub deposit: i32,
+}
+
+/// Summary of a finished run, sent when the run ends
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RoguelikeSettlementDetail {
+    pub game_pass: bool,
+    pub floor: i32,
+    pub step: i32,
+    pub combat: i32,
+    pub emergency: i32,
+    pub boss: i32,
+    pub recruit: i32,
+    pub collection: i32,
+    pub difficulty: String,
+    pub score: i32,
+    pub exp: i32,
+    pub skill: i32,
+}
+
+/// A collapsal paradigm obtained or changed during a Sami run
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RoguelikeCollapsalParadigmsDetail {
+    /// 1 if the collapse deepened, -1 if it weakened
+    pub deepen_or_weaken: i32,
+    pub cur: String,
+    pub prev: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum SubTaskExtraInfoWhat {
+    StageDrops,
+    RecruitTagsDetected,
+    RecruitSpecialTag,
+    RecruitResult,
+    RecruitTagsRefreshed,
+    RecruitTagsSelected,
+    RecruitSlotCompleted,
+    RecruitError,
+    EnterFacility,
+    NotEnoughStaff,
+    ProductOfFacility,
+    StageInfo,
+    StageInfoError,
+    PenguinId,
+    Depot,
+    OperBox,
+    UnsupportedLevel,
+    RoguelikeInvestment,
+    RoguelikeSettlement,
+    RoguelikeCollapsalParadigms,
+    /// An extra info unknown to this version, with its original name
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+/// Details of a `SubTaskExtraInfo` message, keyed by [SubTaskExtraInfoWhat](SubTaskExtraInfoWhat)
+///
+/// Only a `what` unknown to this version falls back to `Unknown`, malformed details of a known one are an error
+///
+/// Roguelike events follow the callback protocol of MaaCore. Entering a stage has no extra info,
+/// see [Task::is_roguelike_stage_enter], and the selected difficulty is only reported in
+/// [RoguelikeSettlementDetail::difficulty]. Shopping and recruiting in a run have no documented
+/// payload, so they are not typed and fall back to `Unknown` if MaaCore ever reports them
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(remote = "Self")]
 #[serde(tag = "what", content = "details")]
 pub enum SubTaskExtraInfoDetails {
     StageDrops(StageDropsDetail),
@@ -253,13 +568,119 @@ pub enum SubTaskExtraInfoDetails {
     Depot(DepotDetail),
     OperBox(OperBoxDetail),
     UnsupportedLevel,
+    /// Originium ingots invested at a roguelike trader
+    RoguelikeInvestment(RoguelikeInvestmentDetail),
+    /// A roguelike run ended
+    RoguelikeSettlement(RoguelikeSettlementDetail),
+    /// A collapsal paradigm obtained or changed during a Sami run
+    RoguelikeCollapsalParadigms(RoguelikeCollapsalParadigmsDetail),
+    /// An extra info unknown to this version, with its original `what` and raw `details`
+    #[serde(skip)]
+    Unknown {
+        what: String,
+        #[serde(default)]
+        details: Value,
+    },
+}
+
+impl SubTaskExtraInfoDetails {
+    pub fn what(&self) -> SubTaskExtraInfoWhat {
+        match self {
+            SubTaskExtraInfoDetails::StageDrops(_) => SubTaskExtraInfoWhat::StageDrops,
+            SubTaskExtraInfoDetails::RecruitTagsDetected(_) => {
+                SubTaskExtraInfoWhat::RecruitTagsDetected
+            }
+            SubTaskExtraInfoDetails::RecruitSpecialTag(_) => {
+                SubTaskExtraInfoWhat::RecruitSpecialTag
+            }
+            SubTaskExtraInfoDetails::RecruitResult(_) => SubTaskExtraInfoWhat::RecruitResult,
+            SubTaskExtraInfoDetails::RecruitTagsRefreshed(_) => {
+                SubTaskExtraInfoWhat::RecruitTagsRefreshed
+            }
+            SubTaskExtraInfoDetails::RecruitTagsSelected(_) => {
+                SubTaskExtraInfoWhat::RecruitTagsSelected
+            }
+            SubTaskExtraInfoDetails::RecruitSlotCompleted => {
+                SubTaskExtraInfoWhat::RecruitSlotCompleted
+            }
+            SubTaskExtraInfoDetails::RecruitError => SubTaskExtraInfoWhat::RecruitError,
+            SubTaskExtraInfoDetails::EnterFacility(_) => SubTaskExtraInfoWhat::EnterFacility,
+            SubTaskExtraInfoDetails::NotEnoughStaff(_) => SubTaskExtraInfoWhat::NotEnoughStaff,
+            SubTaskExtraInfoDetails::ProductOfFacility(_) => {
+                SubTaskExtraInfoWhat::ProductOfFacility
+            }
+            SubTaskExtraInfoDetails::StageInfo(_) => SubTaskExtraInfoWhat::StageInfo,
+            SubTaskExtraInfoDetails::StageInfoError => SubTaskExtraInfoWhat::StageInfoError,
+            SubTaskExtraInfoDetails::PenguinId(_) => SubTaskExtraInfoWhat::PenguinId,
+            SubTaskExtraInfoDetails::Depot(_) => SubTaskExtraInfoWhat::Depot,
+            SubTaskExtraInfoDetails::OperBox(_) => SubTaskExtraInfoWhat::OperBox,
+            SubTaskExtraInfoDetails::UnsupportedLevel => SubTaskExtraInfoWhat::UnsupportedLevel,
+            SubTaskExtraInfoDetails::RoguelikeInvestment(_) => {
+                SubTaskExtraInfoWhat::RoguelikeInvestment
+            }
+            SubTaskExtraInfoDetails::RoguelikeSettlement(_) => {
+                SubTaskExtraInfoWhat::RoguelikeSettlement
+            }
+            SubTaskExtraInfoDetails::RoguelikeCollapsalParadigms(_) => {
+                SubTaskExtraInfoWhat::RoguelikeCollapsalParadigms
+            }
+            SubTaskExtraInfoDetails::Unknown { what, .. } => {
+                SubTaskExtraInfoWhat::Unknown(what.clone())
+            }
+        }
+    }
+}
+
+impl Serialize for SubTaskExtraInfoDetails {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            SubTaskExtraInfoDetails::Unknown { what, details } => {
+                UnknownWhat { what, details }.serialize(serializer)
+            }
+            _ => SubTaskExtraInfoDetails::serialize(self, serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SubTaskExtraInfoDetails {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let mut value: Value = Deserialize::deserialize(deserializer)?;
+        let what: SubTaskExtraInfoWhat =
+            serde_json::from_value(value["what"].clone()).map_err(D::Error::custom)?;
+        match what {
+            SubTaskExtraInfoWhat::Unknown(what) => {
+                let details = value["details"].take();
+                Ok(SubTaskExtraInfoDetails::Unknown { what, details })
+            }
+            _ => {
+                // MaaCore sends `{}` as the details of the kinds without any
+                if value["details"]
+                    .as_object()
+                    .is_some_and(|details| details.is_empty())
+                {
+                    let unit = json!({ "what": value["what"] });
+                    if let Ok(details) = SubTaskExtraInfoDetails::deserialize(unit) {
+                        return Ok(details);
+                    }
+                }
+                SubTaskExtraInfoDetails::deserialize(value).map_err(D::Error::custom)
+            }
+        }
+    }
 }
 
-#[derive(Debug)]
+#[derive(Serialize, Debug)]
 pub struct SubTaskExtraInfoDetail {
     pub taskchain: TaskChain,
     pub class: String,
     pub uuid: String,
+    #[serde(flatten)]
     pub details: SubTaskExtraInfoDetails,
 }
 
@@ -269,16 +690,21 @@ impl<'de> Deserialize<'de> for SubTaskExtraInfoDetail {
         D: serde::Deserializer<'de>,
     {
         let value: Value = Deserialize::deserialize(deserializer)?;
-        let taskchain: TaskChain = serde_json::from_value(value["taskchain"].clone()).unwrap();
-        let class: String = serde_json::from_value(value["class"].clone()).unwrap();
-        let uuid: String = serde_json::from_value(value["uuid"].clone()).unwrap();
-        let what: String = serde_json::from_value(value["what"].clone()).unwrap();
-        let details: Value = serde_json::from_value(value["details"].clone()).unwrap();
+        let taskchain: TaskChain =
+            serde_json::from_value(value["taskchain"].clone()).map_err(D::Error::custom)?;
+        let class: String =
+            serde_json::from_value(value["class"].clone()).map_err(D::Error::custom)?;
+        let uuid: String =
+            serde_json::from_value(value["uuid"].clone()).map_err(D::Error::custom)?;
+        let what: String =
+            serde_json::from_value(value["what"].clone()).map_err(D::Error::custom)?;
+        let details: Value = value["details"].clone();
         let details_json = json!({
             "what":what,
             "details":details,
         });
-        let details: SubTaskExtraInfoDetails = serde_json::from_value(details_json).unwrap();
+        let details: SubTaskExtraInfoDetails =
+            serde_json::from_value(details_json).map_err(D::Error::custom)?;
         Ok(SubTaskExtraInfoDetail {
             taskchain,
             class,
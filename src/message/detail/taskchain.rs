@@ -1,11 +1,12 @@
 use std::fmt::Formatter;
 use std::fmt::Display;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::enum_display;
+use crate::message::{AsstMsgCode, MessageParseError};
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub enum TaskChain {
     StartUp,
     CloseDown,
@@ -24,6 +25,9 @@ pub enum TaskChain {
     SingleStep,
     VideoRecognition,
     Debug,
+    /// A task chain unknown to this version, with its original name
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 enum_display!(
@@ -44,12 +48,13 @@ enum_display!(
     Custom,
     SingleStep,
     VideoRecognition,
-    Debug
+    Debug;
+    Unknown
 );
 
 pub type TaskChainExtraInfoDetail = Value;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum TaskChainStatus {
     TaskChainError,
     TaskChainStart,
@@ -67,38 +72,65 @@ enum_display!(
     TaskChainStopped
 );
 
-impl From<i32> for TaskChainStatus {
-    fn from(value: i32) -> Self {
+impl TryFrom<AsstMsgCode> for TaskChainStatus {
+    type Error = MessageParseError;
+
+    fn try_from(value: AsstMsgCode) -> Result<Self, Self::Error> {
         match value {
-            10000 => TaskChainStatus::TaskChainError,
-            10001 => TaskChainStatus::TaskChainStart,
-            10002 => TaskChainStatus::TaskChainCompleted,
-            10004 => TaskChainStatus::TaskChainStopped,
-            _ => panic!("Unknown TaskChainStatus: {}", value),
+            AsstMsgCode::TaskChainError => Ok(TaskChainStatus::TaskChainError),
+            AsstMsgCode::TaskChainStart => Ok(TaskChainStatus::TaskChainStart),
+            AsstMsgCode::TaskChainCompleted => Ok(TaskChainStatus::TaskChainCompleted),
+            AsstMsgCode::TaskChainExtraInfo => Ok(TaskChainStatus::TaskChainExtraInfo),
+            AsstMsgCode::TaskChainStopped => Ok(TaskChainStatus::TaskChainStopped),
+            _ => Err(MessageParseError::UnknownTaskChainStatusError(value.into())),
         }
     }
 }
 
-#[derive(Debug)]
+impl TryFrom<i32> for TaskChainStatus {
+    type Error = MessageParseError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        AsstMsgCode::try_from(value)
+            .map_err(|_| MessageParseError::UnknownTaskChainStatusError(value))?
+            .try_into()
+    }
+}
+
+impl From<TaskChainStatus> for AsstMsgCode {
+    fn from(value: TaskChainStatus) -> Self {
+        match value {
+            TaskChainStatus::TaskChainError => AsstMsgCode::TaskChainError,
+            TaskChainStatus::TaskChainStart => AsstMsgCode::TaskChainStart,
+            TaskChainStatus::TaskChainCompleted => AsstMsgCode::TaskChainCompleted,
+            TaskChainStatus::TaskChainExtraInfo => AsstMsgCode::TaskChainExtraInfo,
+            TaskChainStatus::TaskChainStopped => AsstMsgCode::TaskChainStopped,
+        }
+    }
+}
+
+/// The status is carried by the message code, so it is not serialized
+#[derive(Serialize, Debug)]
 pub struct TaskChainDetail {
     pub taskchain: TaskChain,
     pub uuid: String,
+    #[serde(skip)]
     pub status: TaskChainStatus,
     pub taskid: i32,
 }
 
 impl TaskChainDetail {
-    pub fn new(msg: i32, detail: &str) -> Self {
-        let status = TaskChainStatus::from(msg);
-        let detail: Value = serde_json::from_str(detail).unwrap();
-        let taskchain: TaskChain = serde_json::from_value(detail["taskchain"].clone()).unwrap();
-        let uuid: String = serde_json::from_value(detail["uuid"].clone()).unwrap();
-        let taskid: i32 = serde_json::from_value(detail["taskid"].clone()).unwrap();
-        TaskChainDetail {
+    pub fn new(msg: AsstMsgCode, detail: &str) -> Result<Self, MessageParseError> {
+        let status = TaskChainStatus::try_from(msg)?;
+        let detail: Value = serde_json::from_str(detail)?;
+        let taskchain: TaskChain = serde_json::from_value(detail["taskchain"].clone())?;
+        let uuid: String = serde_json::from_value(detail["uuid"].clone())?;
+        let taskid: i32 = serde_json::from_value(detail["taskid"].clone())?;
+        Ok(TaskChainDetail {
             taskchain,
             uuid,
             status,
             taskid,
-        }
+        })
     }
 }
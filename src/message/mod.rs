@@ -1,5 +1,11 @@
 pub mod detail;
 
+use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
 use thiserror::Error;
 
 use detail::subtask::*;
@@ -19,6 +25,109 @@ macro_rules! enum_display {
             }
         }
     };
+    ($enum_name:ident,$($variant:ident),*; $unknown:ident) => {
+        impl Display for $enum_name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    $(
+                        $enum_name::$variant => std::write!(f, std::stringify!($variant)),
+                    )*
+                    $enum_name::$unknown(name) => std::write!(f, "{}", name),
+                }
+            }
+        }
+    };
+}
+
+/// Message codes MaaCore calls back with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AsstMsgCode {
+    InternalError,
+    InitFailed,
+    ConnectionInfo,
+    AllTasksCompleted,
+    AsyncCallInfo,
+    /// The instance has been destroyed
+    Destroyed,
+    TaskChainError,
+    TaskChainStart,
+    TaskChainCompleted,
+    TaskChainExtraInfo,
+    TaskChainStopped,
+    SubTaskError,
+    SubTaskStart,
+    SubTaskCompleted,
+    SubTaskExtraInfo,
+    SubTaskStopped,
+}
+
+enum_display!(
+    AsstMsgCode,
+    InternalError,
+    InitFailed,
+    ConnectionInfo,
+    AllTasksCompleted,
+    AsyncCallInfo,
+    Destroyed,
+    TaskChainError,
+    TaskChainStart,
+    TaskChainCompleted,
+    TaskChainExtraInfo,
+    TaskChainStopped,
+    SubTaskError,
+    SubTaskStart,
+    SubTaskCompleted,
+    SubTaskExtraInfo,
+    SubTaskStopped
+);
+
+impl TryFrom<i32> for AsstMsgCode {
+    type Error = MessageParseError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(AsstMsgCode::InternalError),
+            1 => Ok(AsstMsgCode::InitFailed),
+            2 => Ok(AsstMsgCode::ConnectionInfo),
+            3 => Ok(AsstMsgCode::AllTasksCompleted),
+            4 => Ok(AsstMsgCode::AsyncCallInfo),
+            5 => Ok(AsstMsgCode::Destroyed),
+            10000 => Ok(AsstMsgCode::TaskChainError),
+            10001 => Ok(AsstMsgCode::TaskChainStart),
+            10002 => Ok(AsstMsgCode::TaskChainCompleted),
+            10003 => Ok(AsstMsgCode::TaskChainExtraInfo),
+            10004 => Ok(AsstMsgCode::TaskChainStopped),
+            20000 => Ok(AsstMsgCode::SubTaskError),
+            20001 => Ok(AsstMsgCode::SubTaskStart),
+            20002 => Ok(AsstMsgCode::SubTaskCompleted),
+            20003 => Ok(AsstMsgCode::SubTaskExtraInfo),
+            20004 => Ok(AsstMsgCode::SubTaskStopped),
+            _ => Err(MessageParseError::UnknownMessageCodeError(value)),
+        }
+    }
+}
+
+impl From<AsstMsgCode> for i32 {
+    fn from(value: AsstMsgCode) -> Self {
+        match value {
+            AsstMsgCode::InternalError => 0,
+            AsstMsgCode::InitFailed => 1,
+            AsstMsgCode::ConnectionInfo => 2,
+            AsstMsgCode::AllTasksCompleted => 3,
+            AsstMsgCode::AsyncCallInfo => 4,
+            AsstMsgCode::Destroyed => 5,
+            AsstMsgCode::TaskChainError => 10000,
+            AsstMsgCode::TaskChainStart => 10001,
+            AsstMsgCode::TaskChainCompleted => 10002,
+            AsstMsgCode::TaskChainExtraInfo => 10003,
+            AsstMsgCode::TaskChainStopped => 10004,
+            AsstMsgCode::SubTaskError => 20000,
+            AsstMsgCode::SubTaskStart => 20001,
+            AsstMsgCode::SubTaskCompleted => 20002,
+            AsstMsgCode::SubTaskExtraInfo => 20003,
+            AsstMsgCode::SubTaskStopped => 20004,
+        }
+    }
 }
 
 /// Enum for all messages sent by MaaCore
@@ -41,19 +150,34 @@ macro_rules! enum_display {
 /// } else {
 ///   assert!(false);
 /// }
-#[derive(Debug)]
+/// ```
+///
+/// It serializes to the json details MaaCore sent, use `code` to get the message code back
+#[derive(Serialize, Debug)]
+#[serde(untagged)]
 pub enum AsstMessage {
+    #[serde(serialize_with = "serialize_empty")]
     InternalError,
     InitFailed(InitFailedDetail),
     ConnectionInfo(ConnectionInfoDetail),
     AllTasksCompleted(AllTasksCompletedDetail),
     AsyncCallInfo(AsyncCallInfoDetail),
+    #[serde(serialize_with = "serialize_empty")]
+    Destroyed,
     TaskChainInfo(TaskChainDetail),
     TaskChainExtraInfo(TaskChainExtraInfoDetail),
     SubTaskInfo(SubTaskDetail),
     SubTaskExtraInfo(SubTaskExtraInfoDetail),
 }
 
+/// Serialize a message without details as the empty object MaaCore sends
+fn serialize_empty<S>(serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_map(Some(0))?.end()
+}
+
 #[derive(Error, Debug)]
 pub enum MessageParseError {
     #[error("Unknown message code: {0}")]
@@ -61,45 +185,136 @@ pub enum MessageParseError {
 
     #[error("Failed to parse message details: {0}")]
     JsonParseError(#[from] serde_json::Error),
+
+    #[error("Unknown TaskChainStatus: {0}")]
+    UnknownTaskChainStatusError(i32),
+
+    #[error("Unknown SubTaskStatus: {0}")]
+    UnknownSubTaskStatusError(i32),
 }
 
 impl AsstMessage {
+    /// Return the message code MaaCore sent this message with
+    pub fn code(&self) -> AsstMsgCode {
+        match self {
+            AsstMessage::InternalError => AsstMsgCode::InternalError,
+            AsstMessage::InitFailed(_) => AsstMsgCode::InitFailed,
+            AsstMessage::ConnectionInfo(_) => AsstMsgCode::ConnectionInfo,
+            AsstMessage::AllTasksCompleted(_) => AsstMsgCode::AllTasksCompleted,
+            AsstMessage::AsyncCallInfo(_) => AsstMsgCode::AsyncCallInfo,
+            AsstMessage::Destroyed => AsstMsgCode::Destroyed,
+            AsstMessage::TaskChainInfo(detail) => detail.status.into(),
+            AsstMessage::TaskChainExtraInfo(_) => AsstMsgCode::TaskChainExtraInfo,
+            AsstMessage::SubTaskInfo(detail) => detail.status().into(),
+            AsstMessage::SubTaskExtraInfo(_) => AsstMsgCode::SubTaskExtraInfo,
+        }
+    }
+
     pub fn get(msg: i32, details: &str) -> Result<Self, MessageParseError> {
-        match msg {
-            0 => Ok(AsstMessage::InternalError),
-            1 => {
+        let code = AsstMsgCode::try_from(msg)?;
+        match code {
+            AsstMsgCode::InternalError => Ok(AsstMessage::InternalError),
+            AsstMsgCode::InitFailed => {
                 let detail: InitFailedDetail = serde_json::from_str(details)?;
                 Ok(AsstMessage::InitFailed(detail))
             }
-            2 => {
+            AsstMsgCode::ConnectionInfo => {
                 let detail: ConnectionInfoDetail = serde_json::from_str(details)?;
                 Ok(AsstMessage::ConnectionInfo(detail))
             }
-            3 => {
+            AsstMsgCode::AllTasksCompleted => {
                 let detail: AllTasksCompletedDetail = serde_json::from_str(details)?;
                 Ok(AsstMessage::AllTasksCompleted(detail))
             }
-            4 => {
+            AsstMsgCode::AsyncCallInfo => {
                 let detail: AsyncCallInfoDetail = serde_json::from_str(details)?;
                 Ok(AsstMessage::AsyncCallInfo(detail))
             }
-            10000 | 10001 | 10002 | 10004 => {
-                let detail = TaskChainDetail::new(msg, details);
+            AsstMsgCode::Destroyed => Ok(AsstMessage::Destroyed),
+            AsstMsgCode::TaskChainError
+            | AsstMsgCode::TaskChainStart
+            | AsstMsgCode::TaskChainCompleted
+            | AsstMsgCode::TaskChainStopped => {
+                let detail = TaskChainDetail::new(code, details)?;
                 Ok(AsstMessage::TaskChainInfo(detail))
             }
-            10003 => {
+            AsstMsgCode::TaskChainExtraInfo => {
                 let detail: TaskChainExtraInfoDetail = serde_json::from_str(details)?;
                 Ok(AsstMessage::TaskChainExtraInfo(detail))
             }
-            20000 | 20001 | 20002 | 20004 => {
-                let detail = SubTaskDetail::new(msg, details);
+            AsstMsgCode::SubTaskError
+            | AsstMsgCode::SubTaskStart
+            | AsstMsgCode::SubTaskCompleted
+            | AsstMsgCode::SubTaskStopped => {
+                let detail = SubTaskDetail::new(code, details)?;
                 Ok(AsstMessage::SubTaskInfo(detail))
             }
-            20003 => {
+            AsstMsgCode::SubTaskExtraInfo => {
                 let detail: SubTaskExtraInfoDetail = serde_json::from_str(details)?;
                 Ok(AsstMessage::SubTaskExtraInfo(detail))
             }
-            _ => Err(MessageParseError::UnknownMessageCodeError(msg)),
+        }
+    }
+}
+
+static NEXT_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// A parsed `AsstMessage` together with the original message code and json
+///
+/// # Example
+///
+/// ```
+/// use maa_types::message::{AsstMessage, AsstMessageEnvelope};
+///
+/// let first = AsstMessageEnvelope::parse(0, "{}").unwrap();
+/// let second = AsstMessageEnvelope::parse(0, "{}").unwrap();
+///
+/// assert_eq!(first.code, 0);
+/// assert_eq!(first.raw, "{}");
+/// assert!(second.sequence > first.sequence);
+/// assert!(matches!(second.message, AsstMessage::InternalError));
+/// ```
+#[derive(Serialize, Debug)]
+pub struct AsstMessageEnvelope {
+    pub code: i32,
+    pub raw: String,
+    pub received_at: SystemTime,
+    /// Process-wide increasing number, also taken by messages that failed to parse
+    pub sequence: u64,
+    pub message: AsstMessage,
+}
+
+/// A message that failed to parse, with the same metadata an [AsstMessageEnvelope](AsstMessageEnvelope) keeps
+#[derive(Error, Debug)]
+#[error("Failed to parse message #{sequence} with code {code}: {source}")]
+pub struct EnvelopeParseError {
+    pub code: i32,
+    pub raw: String,
+    pub received_at: SystemTime,
+    pub sequence: u64,
+    #[source]
+    pub source: MessageParseError,
+}
+
+impl AsstMessageEnvelope {
+    pub fn parse(msg: i32, details: &str) -> Result<Self, EnvelopeParseError> {
+        let received_at = SystemTime::now();
+        let sequence = NEXT_SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        match AsstMessage::get(msg, details) {
+            Ok(message) => Ok(AsstMessageEnvelope {
+                code: msg,
+                raw: details.to_string(),
+                received_at,
+                sequence,
+                message,
+            }),
+            Err(source) => Err(EnvelopeParseError {
+                code: msg,
+                raw: details.to_string(),
+                received_at,
+                sequence,
+                source,
+            }),
         }
     }
 }
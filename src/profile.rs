@@ -0,0 +1,234 @@
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::task::{AsstTaskParam, TaskParamError};
+
+#[derive(Error, Debug)]
+pub enum ProfileError {
+    #[error("Failed to read or write profile: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Unknown profile format: {0}")]
+    UnknownFormatError(String),
+
+    #[error("Failed to parse json profile: {0}")]
+    JsonParseError(#[from] serde_json::Error),
+
+    #[error("Failed to parse toml profile: {0}")]
+    TomlParseError(#[from] toml::de::Error),
+
+    #[error("Failed to write toml profile: {0}")]
+    TomlWriteError(#[from] toml::ser::Error),
+
+    #[error("{0} is null, which is not representable in toml")]
+    TomlNullError(String),
+
+    #[error("Task #{index} has an empty name")]
+    EmptyNameError { index: usize },
+
+    #[error("Task #{index} ({name}) has a duplicated name")]
+    DuplicateNameError { index: usize, name: String },
+
+    #[error("Task #{index} ({name}) is invalid: {source}")]
+    InvalidTaskError {
+        index: usize,
+        name: String,
+        #[source]
+        source: TaskParamError,
+    },
+}
+
+/// File format of a [TaskProfile](TaskProfile)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileFormat {
+    Json,
+    Toml,
+}
+
+impl ProfileFormat {
+    /// Guess the format from the extension of `path`
+    pub fn from_path(path: &Path) -> Result<Self, ProfileError> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Ok(ProfileFormat::Json),
+            Some("toml") => Ok(ProfileFormat::Toml),
+            _ => Err(ProfileError::UnknownFormatError(path.display().to_string())),
+        }
+    }
+}
+
+/// A named task in a [TaskProfile](TaskProfile)
+#[derive(Serialize, Debug)]
+pub struct ProfileTask {
+    pub name: String,
+    #[serde(flatten)]
+    pub task: AsstTaskParam,
+}
+
+/// The on-disk shape of a [ProfileTask](ProfileTask), parsed first so errors can point at the entry
+#[derive(Deserialize)]
+struct RawProfileTask {
+    #[serde(default)]
+    name: String,
+    #[serde(rename = "type")]
+    #[serde(default)]
+    task_type: Option<String>,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize)]
+struct RawTaskProfile {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    tasks: Vec<RawProfileTask>,
+}
+
+/// An ordered list of named tasks which can be saved to and loaded from a json or toml file
+///
+/// # Example
+///
+/// ```
+/// use maa_types::profile::TaskProfile;
+///
+/// let profile = TaskProfile::from_toml(r#"
+/// name = "daily"
+///
+/// [[tasks]]
+/// name = "wake up"
+/// type = "StartUp"
+///
+/// [[tasks]]
+/// name = "farm"
+/// type = "Fight"
+/// params = { stage = "1-7", medicine = 1 }
+/// "#).unwrap();
+///
+/// assert_eq!(profile.tasks.len(), 2);
+/// assert_eq!(profile.tasks[1].task.name(), "Fight");
+/// ```
+#[derive(Serialize, Debug, Default)]
+pub struct TaskProfile {
+    pub name: String,
+    pub tasks: Vec<ProfileTask>,
+}
+
+impl TaskProfile {
+    pub fn from_json(json: &str) -> Result<Self, ProfileError> {
+        let raw: RawTaskProfile = serde_json::from_str(json)?;
+        Self::from_raw(raw)
+    }
+
+    pub fn from_toml(toml: &str) -> Result<Self, ProfileError> {
+        let raw: RawTaskProfile = toml::from_str(toml)?;
+        Self::from_raw(raw)
+    }
+
+    pub fn to_json(&self) -> Result<String, ProfileError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn to_toml(&self) -> Result<String, ProfileError> {
+        // toml has no null, so drop unset fields and let them fall back to their defaults on load
+        let mut value = serde_json::to_value(self)?;
+        if let Some(tasks) = value["tasks"].as_array_mut() {
+            for task in tasks {
+                if let Some(params) = task["params"].as_object_mut() {
+                    params.retain(|_, v| !v.is_null());
+                }
+            }
+        }
+        check_no_nulls(&value, "")?;
+        Ok(toml::to_string_pretty(&value)?)
+    }
+
+    /// Load a profile, the format is decided by the file extension
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ProfileError> {
+        let path = path.as_ref();
+        let format = ProfileFormat::from_path(path)?;
+        let content = fs::read_to_string(path)?;
+        match format {
+            ProfileFormat::Json => Self::from_json(&content),
+            ProfileFormat::Toml => Self::from_toml(&content),
+        }
+    }
+
+    /// Save the profile, the format is decided by the file extension
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ProfileError> {
+        let path = path.as_ref();
+        let content = match ProfileFormat::from_path(path)? {
+            ProfileFormat::Json => self.to_json()?,
+            ProfileFormat::Toml => self.to_toml()?,
+        };
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    fn from_raw(raw: RawTaskProfile) -> Result<Self, ProfileError> {
+        let mut tasks: Vec<ProfileTask> = Vec::with_capacity(raw.tasks.len());
+        for (index, entry) in raw.tasks.into_iter().enumerate() {
+            if entry.name.is_empty() {
+                return Err(ProfileError::EmptyNameError { index });
+            }
+            if tasks.iter().any(|task| task.name == entry.name) {
+                return Err(ProfileError::DuplicateNameError {
+                    index,
+                    name: entry.name,
+                });
+            }
+            let params = match entry.params {
+                Value::Null => Value::Object(Default::default()),
+                params => params,
+            };
+            let task = match &entry.task_type {
+                Some(task_type) => {
+                    AsstTaskParam::from_name_and_json(task_type, &params.to_string())
+                }
+                None => Err(TaskParamError::MissingTaskTypeError),
+            }
+            .map_err(|source| ProfileError::InvalidTaskError {
+                index,
+                name: entry.name.clone(),
+                source,
+            })?;
+            tasks.push(ProfileTask {
+                name: entry.name,
+                task,
+            });
+        }
+        Ok(TaskProfile {
+            name: raw.name,
+            tasks,
+        })
+    }
+}
+
+/// Refuse the nulls left after dropping unset fields, they are part of free-form values
+fn check_no_nulls(value: &Value, path: &str) -> Result<(), ProfileError> {
+    let join = |key: &dyn std::fmt::Display| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", path, key)
+        }
+    };
+    match value {
+        Value::Null => return Err(ProfileError::TomlNullError(path.to_string())),
+        Value::Object(map) => {
+            for (key, v) in map {
+                check_no_nulls(v, &join(key))?;
+            }
+        }
+        Value::Array(array) => {
+            for (index, v) in array.iter().enumerate() {
+                check_no_nulls(v, &join(&index))?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
@@ -1,12 +1,78 @@
+pub mod copilot;
+pub mod infrast_plan;
+pub mod sss_copilot;
+
 use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
 use serde_default::DefaultFromSerde;
+use serde_json::Value;
+use thiserror::Error;
+
+/// A problem found in a task parameter, addressed by the field it is in
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{field}: {reason}")]
+pub struct ValidationError {
+    pub field: String,
+    pub reason: String,
+}
+
+impl ValidationError {
+    pub fn new(field: impl Into<String>, reason: impl Into<String>) -> Self {
+        ValidationError {
+            field: field.into(),
+            reason: reason.into(),
+        }
+    }
+}
 
 pub trait Param: Serialize {
+    /// Fields which MaaCore allows to change while the task is running
+    const RUNTIME_FIELDS: &'static [&'static str] = &["enable"];
+
     fn json(&self) -> String {
         serde_json::to_string(self).unwrap()
     }
+
+    /// Return the json patch to send to MaaCore to change a running task from `self` to `new`
+    ///
+    /// Only the changed fields are included, it fails if any of them can not be changed at runtime
+    fn runtime_patch(&self, new: &Self) -> Result<String, TaskParamError> {
+        let old = serde_json::to_value(self)?;
+        let new = serde_json::to_value(new)?;
+        let mut patch = serde_json::Map::new();
+        if let (Value::Object(old), Value::Object(new)) = (old, new) {
+            for (field, value) in new {
+                if old.get(&field) == Some(&value) {
+                    continue;
+                }
+                if !Self::RUNTIME_FIELDS.contains(&field.as_str()) {
+                    return Err(TaskParamError::NotRuntimeModifiableError(field));
+                }
+                patch.insert(field, value);
+            }
+        }
+        Ok(Value::Object(patch).to_string())
+    }
+
+    /// Check the values before they are sent to MaaCore, return all the problems found
+    fn validate(&self) -> Vec<ValidationError> {
+        Vec::new()
+    }
+}
+
+fn check_non_negative(errors: &mut Vec<ValidationError>, field: &str, value: i32) {
+    if value < 0 {
+        errors.push(ValidationError::new(field, "must not be negative"));
+    }
+}
+
+fn check_not_empty(errors: &mut Vec<ValidationError>, field: &str, value: &str) {
+    if value.is_empty() {
+        errors.push(ValidationError::new(field, "must not be empty"));
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -57,12 +123,136 @@ pub enum Server {
     KR,
 }
 
+/// Code of a stage to fight
+///
+/// It is (de)serialized as the string MaaCore expects
+///
+/// # Example
+///
+/// ```
+/// use maa_types::task::StageCode;
+///
+/// let stage: StageCode = "CE-6".parse().unwrap();
+/// assert!(matches!(stage, StageCode::Resource(_)));
+/// assert_eq!(stage.to_string(), "CE-6");
+/// assert!("ce-6".parse::<StageCode>().is_err());
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(try_from = "String", into = "String")]
+pub enum StageCode {
+    /// The current or last stage, serialized as an empty string
+    #[default]
+    Current,
+    Last,
+    /// Main story stages, such as `1-7`, `S4-1`, `H8-4`, `R8-11`, `JT8-2` or `10-1-HARD`
+    MainStory(String),
+    /// Resource stages, such as `CE-6`, `LS-6` or `PR-A-2`
+    Resource(String),
+    /// Annihilation, optionally with a map name such as `Chernobog@Annihilation`
+    Annihilation(Option<String>),
+    /// Event stages, such as `IC-9` or `SV-EX-8`
+    Event(String),
+}
+
+fn is_stage_number(s: &str) -> bool {
+    (1..=2).contains(&s.len()) && s.chars().all(|c| c.is_ascii_digit())
+}
+
+/// A chapter number, optionally prefixed by uppercase letters such as `S`, `H`, `R` or `JT`
+fn is_main_story_chapter(s: &str) -> bool {
+    is_stage_number(s.trim_start_matches(|c: char| c.is_ascii_uppercase()))
+}
+
+fn is_event_part(s: &str) -> bool {
+    !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+}
+
+impl FromStr for StageCode {
+    type Err = TaskParamError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" | "Current" => return Ok(StageCode::Current),
+            "Last" => return Ok(StageCode::Last),
+            "Annihilation" => return Ok(StageCode::Annihilation(None)),
+            _ => {}
+        }
+
+        if let Some(map) = s.strip_suffix("@Annihilation") {
+            if !map.is_empty() && map.chars().all(|c| c.is_ascii_alphanumeric()) {
+                return Ok(StageCode::Annihilation(Some(map.to_string())));
+            }
+            return Err(TaskParamError::InvalidStageCodeError(s.to_string()));
+        }
+
+        let parts: Vec<&str> = s.split('-').collect();
+        let stage = match parts.as_slice() {
+            ["CE" | "LS", n] if matches!(*n, "1" | "2" | "3" | "4" | "5" | "6") => {
+                StageCode::Resource(s.to_string())
+            }
+            ["CA" | "AP" | "SK", n] if matches!(*n, "1" | "2" | "3" | "4" | "5") => {
+                StageCode::Resource(s.to_string())
+            }
+            ["PR", "A" | "B" | "C" | "D", "1" | "2"] => StageCode::Resource(s.to_string()),
+            ["CE" | "LS" | "CA" | "AP" | "SK" | "PR", ..] => {
+                return Err(TaskParamError::InvalidStageCodeError(s.to_string()))
+            }
+            [chapter, n] | [chapter, n, "HARD" | "NORMAL"]
+                if is_main_story_chapter(chapter) && is_stage_number(n) =>
+            {
+                StageCode::MainStory(s.to_string())
+            }
+            [event, rest @ .., last]
+                if (2..=4).contains(&event.len())
+                    && event.chars().all(|c| c.is_ascii_uppercase())
+                    && rest.iter().all(|part| is_event_part(part))
+                    && is_event_part(last)
+                    && last.chars().any(|c| c.is_ascii_digit()) =>
+            {
+                StageCode::Event(s.to_string())
+            }
+            _ => return Err(TaskParamError::InvalidStageCodeError(s.to_string())),
+        };
+        Ok(stage)
+    }
+}
+
+impl Display for StageCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StageCode::Current => Ok(()),
+            StageCode::Last => write!(f, "Last"),
+            StageCode::Annihilation(None) => write!(f, "Annihilation"),
+            StageCode::Annihilation(Some(map)) => write!(f, "{}@Annihilation", map),
+            StageCode::MainStory(code) | StageCode::Resource(code) | StageCode::Event(code) => {
+                write!(f, "{}", code)
+            }
+        }
+    }
+}
+
+impl TryFrom<String> for StageCode {
+    type Error = TaskParamError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<StageCode> for String {
+    fn from(value: StageCode) -> Self {
+        value.to_string()
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, DefaultFromSerde)]
 pub struct FightParams {
     #[serde(default = "def_true")]
     pub enable: bool,
     #[serde(default)]
-    pub stage: Option<String>,
+    pub stage: StageCode,
     #[serde(default)]
     pub medicine: i32,
     #[serde(default)]
@@ -86,7 +276,29 @@ pub struct FightParams {
     pub dr_grandet: bool,
 }
 
-impl Param for FightParams {}
+impl Param for FightParams {
+    const RUNTIME_FIELDS: &'static [&'static str] = &[
+        "enable",
+        "stage",
+        "medicine",
+        "expiring_medicine",
+        "stone",
+        "times",
+        "drops",
+    ];
+
+    fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        check_non_negative(&mut errors, "medicine", self.medicine);
+        check_non_negative(&mut errors, "expiring_medicine", self.expiring_medicine);
+        check_non_negative(&mut errors, "stone", self.stone);
+        check_non_negative(&mut errors, "times", self.times);
+        for (item, count) in &self.drops {
+            check_non_negative(&mut errors, &format!("drops.{}", item), *count);
+        }
+        errors
+    }
+}
 
 fn def_rec_time() -> HashMap<String, i32> {
     let mut rec_time: HashMap<String, i32> = HashMap::new();
@@ -132,7 +344,45 @@ pub struct RecruitParams {
     pub server: Option<Server>,
 }
 
-impl Param for RecruitParams {}
+fn is_recruit_level(level: i32) -> bool {
+    (3..=6).contains(&level)
+}
+
+impl Param for RecruitParams {
+    fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        for (index, level) in self.select.iter().enumerate() {
+            if !is_recruit_level(*level) {
+                errors.push(ValidationError::new(
+                    format!("select.{}", index),
+                    "must be between 3 and 6",
+                ));
+            }
+        }
+        for (index, level) in self.confirm.iter().enumerate() {
+            if !is_recruit_level(*level) {
+                errors.push(ValidationError::new(
+                    format!("confirm.{}", index),
+                    "must be between 3 and 6",
+                ));
+            }
+        }
+        check_non_negative(&mut errors, "times", self.times);
+        check_non_negative(&mut errors, "expedite_times", self.expedite_times);
+        for (level, minutes) in &self.recruitment_time {
+            let field = format!("recruitment_time.{}", level);
+            if !level.parse().is_ok_and(is_recruit_level) {
+                errors.push(ValidationError::new(field, "level must be between 3 and 6"));
+            } else if !(60..=540).contains(minutes) || minutes % 10 != 0 {
+                errors.push(ValidationError::new(
+                    field,
+                    "must be between 60 and 540 minutes in steps of 10",
+                ));
+            }
+        }
+        errors
+    }
+}
 
 #[derive(Serialize, Deserialize, Debug)]
 pub enum InfrastFacility {
@@ -156,6 +406,7 @@ pub enum DronesUsage {
     Chip,
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for DronesUsage {
     fn default() -> Self {
         DronesUsage::_NotUse
@@ -178,12 +429,86 @@ fn def_threshold() -> f64 {
     0.3
 }
 
+/// Mode of the infrast task
+///
+/// It is (de)serialized as the `mode` number MaaCore expects, with `filename` and `plan_index` for the custom plan mode
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(try_from = "RawInfrastMode", into = "RawInfrastMode")]
+pub enum InfrastMode {
+    #[default]
+    Default,
+    /// Use the plan at `plan_index` in the custom plan file `filename`
+    Custom {
+        filename: String,
+        plan_index: i32,
+    },
+    Rotation,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct RawInfrastMode {
+    #[serde(default)]
+    mode: i32,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filename: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    plan_index: Option<i32>,
+}
+
+impl From<InfrastMode> for RawInfrastMode {
+    fn from(value: InfrastMode) -> Self {
+        match value {
+            InfrastMode::Default => RawInfrastMode {
+                mode: 0,
+                filename: None,
+                plan_index: None,
+            },
+            InfrastMode::Custom {
+                filename,
+                plan_index,
+            } => RawInfrastMode {
+                mode: 10000,
+                filename: Some(filename),
+                plan_index: Some(plan_index),
+            },
+            InfrastMode::Rotation => RawInfrastMode {
+                mode: 20000,
+                filename: None,
+                plan_index: None,
+            },
+        }
+    }
+}
+
+impl TryFrom<RawInfrastMode> for InfrastMode {
+    type Error = String;
+
+    fn try_from(value: RawInfrastMode) -> Result<Self, Self::Error> {
+        match value.mode {
+            0 => Ok(InfrastMode::Default),
+            10000 => match (value.filename, value.plan_index) {
+                (Some(filename), Some(plan_index)) => Ok(InfrastMode::Custom {
+                    filename,
+                    plan_index,
+                }),
+                (None, _) => Err("Custom infrast mode requires a filename".to_string()),
+                (_, None) => Err("Custom infrast mode requires a plan_index".to_string()),
+            },
+            20000 => Ok(InfrastMode::Rotation),
+            _ => Err(format!("Unknown InfrastMode: {}", value.mode)),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, DefaultFromSerde)]
 pub struct InfrastParams {
     #[serde(default = "def_true")]
     pub enable: bool,
+    #[serde(flatten)]
     #[serde(default)]
-    pub mode: i32,
+    pub mode: InfrastMode,
     #[serde(default = "def_facility")]
     pub facility: Vec<InfrastFacility>,
     #[serde(default)]
@@ -196,13 +521,34 @@ pub struct InfrastParams {
     pub dorm_notstationed_enabled: bool,
     #[serde(default)]
     pub dorm_trust_enabled: bool,
-    #[serde(default)]
-    pub filename: Option<String>,
-    #[serde(default)]
-    pub plan_index: Option<i32>,
 }
 
-impl Param for InfrastParams {}
+impl Param for InfrastParams {
+    const RUNTIME_FIELDS: &'static [&'static str] = &[
+        "enable",
+        "drones",
+        "threshold",
+        "replenish",
+        "dorm_notstationed_enabled",
+        "dorm_trust_enabled",
+    ];
+
+    fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        if !(0.0..=1.0).contains(&self.threshold) {
+            errors.push(ValidationError::new("threshold", "must be between 0 and 1"));
+        }
+        if let InfrastMode::Custom {
+            filename,
+            plan_index,
+        } = &self.mode
+        {
+            check_not_empty(&mut errors, "filename", filename);
+            check_non_negative(&mut errors, "plan_index", *plan_index);
+        }
+        errors
+    }
+}
 
 #[derive(Serialize, Deserialize, Debug, DefaultFromSerde)]
 pub struct MallParams {
@@ -222,14 +568,62 @@ impl Param for MallParams {}
 
 pub type AwardParams = CloseDownParams;
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub enum RoguelikeTheme {
+    #[default]
+    Phantom,
+    Mizuki,
+    Sami,
+    Sarkaz,
+    JieGarden,
+    /// A theme unknown to this version, with its original name
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+impl RoguelikeTheme {
+    /// Return whether MaaCore supports `mode` for this theme, unknown themes are left to MaaCore
+    pub fn supports(&self, mode: RoguelikeMode) -> bool {
+        if let RoguelikeTheme::Unknown(_) = self {
+            return true;
+        }
+        match mode {
+            RoguelikeMode::Exp | RoguelikeMode::Investment | RoguelikeMode::Collectible => true,
+            RoguelikeMode::CollapsalParadigms => *self == RoguelikeTheme::Sami,
+            RoguelikeMode::MonthlySquad | RoguelikeMode::DeepExploration => {
+                *self != RoguelikeTheme::Phantom
+            }
+        }
+    }
+}
+
+int_enum! {
+    #[derive(Default)]
+    RoguelikeMode {
+        /// Farm exp, go as far as possible
+        #[default]
+        Exp = 0,
+        /// Farm originium ingots, quit after the first stage
+        Investment = 1,
+        /// Reset the run until a good opening collectible is obtained
+        Collectible = 4,
+        /// Farm collapsal paradigms, `Sami` only
+        CollapsalParadigms = 5,
+        /// Farm monthly squad rewards
+        MonthlySquad = 6,
+        /// Farm deep exploration rewards
+        DeepExploration = 7,
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, DefaultFromSerde)]
 pub struct RoguelikeParams {
     #[serde(default = "def_true")]
     pub enable: bool,
     #[serde(default)]
-    pub theme: Option<String>,
+    pub theme: RoguelikeTheme,
     #[serde(default)]
-    pub mode: i32,
+    pub mode: RoguelikeMode,
     #[serde(default = "def_i32_max")]
     pub starts_count: i32,
     #[serde(default = "def_true")]
@@ -252,7 +646,239 @@ pub struct RoguelikeParams {
     pub refresh_trader_with_dice: bool,
 }
 
-impl Param for RoguelikeParams {}
+impl Param for RoguelikeParams {
+    fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        if !self.theme.supports(self.mode) {
+            errors.push(ValidationError::new(
+                "mode",
+                format!("{:?} is not supported by theme {:?}", self.mode, self.theme),
+            ));
+        }
+        check_non_negative(&mut errors, "starts_count", self.starts_count);
+        check_non_negative(&mut errors, "investments_count", self.investments_count);
+        errors
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CopilotUserAdditional {
+    pub name: String,
+    pub skill: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultFromSerde)]
+pub struct CopilotParams {
+    #[serde(default = "def_true")]
+    pub enable: bool,
+    #[serde(default)]
+    pub filename: String,
+    #[serde(default)]
+    pub formation: bool,
+    #[serde(default)]
+    pub support_unit_name: Option<String>,
+    #[serde(default)]
+    pub add_trust: bool,
+    #[serde(default)]
+    pub add_user_additional: bool,
+    #[serde(default)]
+    pub user_additional: Vec<CopilotUserAdditional>,
+    #[serde(default)]
+    pub loop_times: Option<i32>,
+    #[serde(default)]
+    pub use_sanity_potion: bool,
+    #[serde(default)]
+    pub need_navigate: bool,
+    #[serde(default)]
+    pub navigate_name: Option<String>,
+    #[serde(default)]
+    pub is_raid: bool,
+}
+
+impl Param for CopilotParams {
+    fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        check_not_empty(&mut errors, "filename", &self.filename);
+        if let Some(loop_times) = self.loop_times {
+            check_non_negative(&mut errors, "loop_times", loop_times);
+        }
+        errors
+    }
+}
+
+fn def_loop_times() -> i32 {
+    1
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultFromSerde)]
+pub struct SSSCopilotParams {
+    #[serde(default = "def_true")]
+    pub enable: bool,
+    #[serde(default)]
+    pub filename: String,
+    #[serde(default = "def_loop_times")]
+    pub loop_times: i32,
+}
+
+impl Param for SSSCopilotParams {
+    fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        check_not_empty(&mut errors, "filename", &self.filename);
+        check_non_negative(&mut errors, "loop_times", self.loop_times);
+        errors
+    }
+}
+
+pub type DepotParams = CloseDownParams;
+
+pub type OperBoxParams = CloseDownParams;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReclamationAlgorithmTheme {
+    /// Fire Within the Sand
+    #[default]
+    Fire,
+    /// Tales Within the Sand
+    Tales,
+}
+
+int_enum! {
+    #[derive(Default)]
+    ReclamationAlgorithmMode {
+        /// Farm badges and construction points
+        #[default]
+        FarmPoints = 0,
+        /// Farm pure gold for `Fire`, craft `tools_to_craft` for `Tales`
+        Craft = 1,
+    }
+}
+
+int_enum! {
+    #[derive(Default)]
+    ReclamationIncrementMode {
+        #[default]
+        Click = 0,
+        Hold = 1,
+    }
+}
+
+fn def_num_craft_batches() -> i32 {
+    16
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultFromSerde)]
+pub struct ReclamationAlgorithmParams {
+    #[serde(default = "def_true")]
+    pub enable: bool,
+    #[serde(default)]
+    pub theme: ReclamationAlgorithmTheme,
+    #[serde(default)]
+    pub mode: ReclamationAlgorithmMode,
+    #[serde(default)]
+    pub tools_to_craft: Vec<String>,
+    #[serde(default)]
+    pub increment_mode: ReclamationIncrementMode,
+    #[serde(default = "def_num_craft_batches")]
+    pub num_craft_batches: i32,
+}
+
+impl Param for ReclamationAlgorithmParams {
+    fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        if self.num_craft_batches < 1 {
+            errors.push(ValidationError::new(
+                "num_craft_batches",
+                "must be positive",
+            ));
+        }
+        errors
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultFromSerde)]
+pub struct CustomParams {
+    #[serde(default = "def_true")]
+    pub enable: bool,
+    /// Names of the tasks in the resource pipeline to run
+    #[serde(default)]
+    pub task_names: Vec<String>,
+}
+
+impl Param for CustomParams {}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub enum SingleStepType {
+    #[default]
+    #[serde(rename = "copilot")]
+    Copilot,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SingleStepSubtask {
+    /// Set the stage name, `details` is `{"stage": "..."}`
+    #[default]
+    Stage,
+    /// Start the battle, `details` is unused
+    Start,
+    /// Perform a single copilot action, `details` is the action object
+    Action,
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultFromSerde)]
+pub struct SingleStepParams {
+    #[serde(default = "def_true")]
+    pub enable: bool,
+    #[serde(rename = "type")]
+    #[serde(default)]
+    pub step_type: SingleStepType,
+    #[serde(default)]
+    pub subtask: SingleStepSubtask,
+    #[serde(default)]
+    pub details: Value,
+}
+
+impl Param for SingleStepParams {}
+
+#[derive(Serialize, Deserialize, Debug, DefaultFromSerde)]
+pub struct VideoRecognitionParams {
+    #[serde(default = "def_true")]
+    pub enable: bool,
+    /// Path of the video file to recognize
+    #[serde(default)]
+    pub filename: String,
+}
+
+impl Param for VideoRecognitionParams {
+    fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        check_not_empty(&mut errors, "filename", &self.filename);
+        errors
+    }
+}
+
+pub type DebugParams = CloseDownParams;
+
+#[derive(Error, Debug)]
+pub enum TaskParamError {
+    #[error("Unknown task name: {0}")]
+    UnknownTaskNameError(String),
+
+    #[error("Missing task type")]
+    MissingTaskTypeError,
+
+    #[error("Failed to parse task params: {0}")]
+    JsonParseError(#[from] serde_json::Error),
+
+    #[error("Invalid stage code: {0}")]
+    InvalidStageCodeError(String),
+
+    #[error("Task type changed from {0} to {1}")]
+    TaskTypeMismatchError(String, String),
+
+    #[error("Field {0} can not be changed while the task is running")]
+    NotRuntimeModifiableError(String),
+}
 
 macro_rules! asst_task_param {
     ($($enumvariant: ident($content: ty),)*) => {
@@ -260,6 +886,8 @@ macro_rules! asst_task_param {
         /// 
         /// All the task parameters implement [Param](Param) and [Default](std::default::Default) trait
         /// 
+        /// It (de)serializes as `{"type": "<name>", "params": {...}}`
+        /// 
         /// Example:
         /// ```
         /// use maa_types::task::AsstTaskParam;
@@ -267,13 +895,22 @@ macro_rules! asst_task_param {
         /// 
         /// let startup = AsstTaskParam::StartUp(StartUpParams::default());
         /// assert_eq!(startup.name(), "StartUp");
-        #[derive(Debug)]
+        #[derive(Serialize, Deserialize, Debug)]
+        #[serde(tag = "type", content = "params")]
         pub enum AsstTaskParam {
             $($enumvariant($content),)*
         }
 
         impl AsstTaskParam {
 
+            /// Build a task parameter from its name and json string, the inverse of [name](AsstTaskParam::name) and [param](AsstTaskParam::param)
+            pub fn from_name_and_json(name: &str, json: &str) -> Result<Self, TaskParamError> {
+                match name {
+                    $(stringify!($enumvariant) => Ok(AsstTaskParam::$enumvariant(serde_json::from_str(json)?)),)*
+                    _ => Err(TaskParamError::UnknownTaskNameError(name.to_string())),
+                }
+            }
+
             /// Return the name of the task parameter
             pub fn name(&self) -> String {
                 match self {
@@ -288,6 +925,21 @@ macro_rules! asst_task_param {
                 }
             }
 
+            /// Check the task parameter, see [Param::validate](Param::validate)
+            pub fn validate(&self) -> Vec<ValidationError> {
+                match self {
+                    $(AsstTaskParam::$enumvariant(content) => content.validate(),)*
+                }
+            }
+
+            /// Return the json patch to change a running task from `self` to `new`, see [Param::runtime_patch](Param::runtime_patch)
+            pub fn runtime_patch(&self, new: &AsstTaskParam) -> Result<String, TaskParamError> {
+                match (self, new) {
+                    $((AsstTaskParam::$enumvariant(old), AsstTaskParam::$enumvariant(new)) => old.runtime_patch(new),)*
+                    _ => Err(TaskParamError::TaskTypeMismatchError(self.name(), new.name())),
+                }
+            }
+
             /// Return whether the task parameter is enabled
             pub fn enabled(&self) -> bool {
                 match self {
@@ -307,4 +959,13 @@ asst_task_param! {
     Mall(MallParams),
     Award(AwardParams),
     Roguelike(RoguelikeParams),
+    Copilot(CopilotParams),
+    SSSCopilot(SSSCopilotParams),
+    Depot(DepotParams),
+    OperBox(OperBoxParams),
+    ReclamationAlgorithm(ReclamationAlgorithmParams),
+    Custom(CustomParams),
+    SingleStep(SingleStepParams),
+    VideoRecognition(VideoRecognitionParams),
+    Debug(DebugParams),
 }
@@ -0,0 +1,399 @@
+//! Types for the copilot job files used by [CopilotParams](super::CopilotParams)
+
+use std::collections::HashSet;
+
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use super::ValidationError;
+
+int_enum! {
+    #[derive(Default)]
+    SkillUsage {
+        /// Do not use the skill automatically
+        #[default]
+        NotUse = 0,
+        /// Use the skill whenever it is ready
+        WhenReady = 1,
+        /// Use the skill `skill_times` times
+        Times = 2,
+        /// Let MaaCore decide when to use the skill
+        Auto = 3,
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct OperatorRequirements {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elite: Option<i32>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level: Option<i32>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skill_level: Option<i32>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub module: Option<i32>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub potentiality: Option<i32>,
+}
+
+fn def_skill() -> i32 {
+    1
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CopilotOperator {
+    pub name: String,
+    #[serde(default = "def_skill")]
+    pub skill: i32,
+    #[serde(default)]
+    pub skill_usage: SkillUsage,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skill_times: Option<i32>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requirements: Option<OperatorRequirements>,
+}
+
+/// A group of operators of which any one can be deployed under the group name
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CopilotGroup {
+    pub name: String,
+    pub opers: Vec<CopilotOperator>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Direction {
+    #[serde(alias = "左")]
+    Left,
+    #[default]
+    #[serde(alias = "右")]
+    Right,
+    #[serde(alias = "上")]
+    Up,
+    #[serde(alias = "下")]
+    Down,
+    #[serde(alias = "无")]
+    None,
+}
+
+/// Kind of a [CopilotAction](CopilotAction), keyed by `type`
+///
+/// As in MaaCore, `type` is case-insensitive and defaults to `Deploy` when missing
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(remote = "Self")]
+#[serde(tag = "type")]
+pub enum CopilotActionKind {
+    #[serde(alias = "部署")]
+    Deploy {
+        name: String,
+        location: [i32; 2],
+        #[serde(default)]
+        direction: Direction,
+    },
+    #[serde(alias = "技能")]
+    Skill {
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        location: Option<[i32; 2]>,
+    },
+    #[serde(alias = "撤退")]
+    Retreat {
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        location: Option<[i32; 2]>,
+    },
+    #[serde(alias = "二倍速")]
+    SpeedUp,
+    #[serde(alias = "子弹时间")]
+    BulletTime,
+    #[serde(alias = "技能用法")]
+    SkillUsage {
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        location: Option<[i32; 2]>,
+        skill_usage: SkillUsage,
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        skill_times: Option<i32>,
+    },
+    /// Print `doc` in the log
+    #[serde(alias = "打印")]
+    Output,
+    /// Do nothing but use skills until the battle ends
+    #[serde(alias = "摆完挂机")]
+    SkillDaemon,
+    #[serde(alias = "移动镜头")]
+    MoveCamera { distance: [f64; 2] },
+    /// Draw an operator card, SSS only
+    #[serde(alias = "调配干员")]
+    DrawCard,
+    /// Restart the stage if the operator `name` has not been drawn, SSS only
+    #[serde(alias = "检查重开")]
+    CheckIfStartOver {
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+    },
+}
+
+const ACTION_TYPES: &[&str] = &[
+    "Deploy",
+    "Skill",
+    "Retreat",
+    "SpeedUp",
+    "BulletTime",
+    "SkillUsage",
+    "Output",
+    "SkillDaemon",
+    "MoveCamera",
+    "DrawCard",
+    "CheckIfStartOver",
+];
+
+impl Serialize for CopilotActionKind {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        CopilotActionKind::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CopilotActionKind {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut map: Map<String, Value> = Deserialize::deserialize(deserializer)?;
+        let action_type = match map.get("type") {
+            None => Some("Deploy"),
+            Some(Value::String(action_type)) => ACTION_TYPES
+                .iter()
+                .find(|name| name.eq_ignore_ascii_case(action_type))
+                .copied(),
+            Some(_) => None,
+        };
+        if let Some(action_type) = action_type {
+            map.insert("type".to_string(), Value::from(action_type));
+        }
+        CopilotActionKind::deserialize(Value::Object(map)).map_err(D::Error::custom)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CopilotAction {
+    #[serde(flatten)]
+    pub kind: CopilotActionKind,
+    /// Wait until the number of killed enemies reaches this value
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kills: Option<i32>,
+    /// Wait until the deployment cost reaches this value
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub costs: Option<i32>,
+    /// Wait until the deployment cost changes by this value
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost_changes: Option<i32>,
+    /// Wait until this many operators are cooling down, -1 for any
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cooling: Option<i32>,
+    /// Delay in milliseconds before the action
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pre_delay: Option<i32>,
+    /// Delay in milliseconds after the action
+    #[serde(default)]
+    #[serde(alias = "rear_delay")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_delay: Option<i32>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doc: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doc_color: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CopilotDoc {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title_color: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details_color: Option<String>,
+}
+
+/// A copilot job file
+///
+/// # Example
+///
+/// ```
+/// use maa_types::task::copilot::{CopilotActionKind, CopilotFile};
+///
+/// let file = CopilotFile::from_json(r#"{
+///     "stage_name": "obt/main/level_main_01-07",
+///     "opers": [{ "name": "Exusiai", "skill": 3, "skill_usage": 1 }],
+///     "actions": [
+///         { "type": "Deploy", "name": "Exusiai", "location": [5, 3], "direction": "Left", "costs": 12 },
+///         { "type": "SpeedUp" }
+///     ]
+/// }"#).unwrap();
+///
+/// assert!(matches!(file.actions[0].kind, CopilotActionKind::Deploy { .. }));
+/// assert!(file.validate().is_empty());
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CopilotFile {
+    pub stage_name: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minimum_required: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doc: Option<CopilotDoc>,
+    #[serde(default)]
+    pub opers: Vec<CopilotOperator>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<CopilotGroup>,
+    #[serde(default)]
+    pub actions: Vec<CopilotAction>,
+}
+
+pub(super) fn validate_operator(
+    field: &str,
+    operator: &CopilotOperator,
+    errors: &mut Vec<ValidationError>,
+) {
+    if !(1..=3).contains(&operator.skill) {
+        errors.push(ValidationError::new(
+            format!("{}.skill", field),
+            "must be between 1 and 3",
+        ));
+    }
+    if operator.skill_usage == SkillUsage::Times && operator.skill_times.is_none() {
+        errors.push(ValidationError::new(
+            format!("{}.skill_times", field),
+            "is required when skill_usage is Times",
+        ));
+    }
+}
+
+pub(super) fn validate_action(
+    field: &str,
+    action: &CopilotAction,
+    errors: &mut Vec<ValidationError>,
+) {
+    let (name, location) = match &action.kind {
+        CopilotActionKind::Skill { name, location }
+        | CopilotActionKind::Retreat { name, location }
+        | CopilotActionKind::SkillUsage { name, location, .. } => (name, location),
+        _ => return,
+    };
+    if name.is_none() && location.is_none() {
+        errors.push(ValidationError::new(
+            field,
+            "requires either name or location",
+        ));
+    }
+    if let CopilotActionKind::SkillUsage {
+        skill_usage: SkillUsage::Times,
+        skill_times: None,
+        ..
+    } = action.kind
+    {
+        errors.push(ValidationError::new(
+            format!("{}.skill_times", field),
+            "is required when skill_usage is Times",
+        ));
+    }
+}
+
+impl CopilotFile {
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Check the job, return all the problems found
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        if self.stage_name.is_empty() {
+            errors.push(ValidationError::new("stage_name", "must not be empty"));
+        }
+
+        let mut names = HashSet::new();
+        for (index, operator) in self.opers.iter().enumerate() {
+            let field = format!("opers.{}", index);
+            if !names.insert(operator.name.as_str()) {
+                errors.push(ValidationError::new(
+                    format!("{}.name", field),
+                    format!("{} is duplicated", operator.name),
+                ));
+            }
+            validate_operator(&field, operator, &mut errors);
+        }
+        for (index, group) in self.groups.iter().enumerate() {
+            let field = format!("groups.{}", index);
+            if !names.insert(group.name.as_str()) {
+                errors.push(ValidationError::new(
+                    format!("{}.name", field),
+                    format!("{} is duplicated", group.name),
+                ));
+            }
+            for (index, operator) in group.opers.iter().enumerate() {
+                validate_operator(&format!("{}.opers.{}", field, index), operator, &mut errors);
+            }
+        }
+
+        for (index, action) in self.actions.iter().enumerate() {
+            let field = format!("actions.{}", index);
+            match &action.kind {
+                CopilotActionKind::Deploy { name, .. } if !names.contains(name.as_str()) => {
+                    errors.push(ValidationError::new(
+                        format!("{}.name", field),
+                        format!("{} is not in opers or groups", name),
+                    ));
+                }
+                CopilotActionKind::DrawCard | CopilotActionKind::CheckIfStartOver { .. } => {
+                    errors.push(ValidationError::new(
+                        format!("{}.type", field),
+                        "is only available in SSS copilot files",
+                    ));
+                }
+                _ => {}
+            }
+            validate_action(&field, action, &mut errors);
+        }
+        errors
+    }
+}
@@ -0,0 +1,328 @@
+//! Types for the custom infrast plan files used by [InfrastMode::Custom](super::InfrastMode::Custom)
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use super::ValidationError;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PlanOrder {
+    /// Before the operators of the plan are assigned
+    Pre,
+    /// After the operators of the plan are assigned
+    Post,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanProduct {
+    #[serde(rename = "Battle Record")]
+    BattleRecord,
+    #[serde(rename = "Pure Gold")]
+    PureGold,
+    #[serde(rename = "Dualchip")]
+    Dualchip,
+    #[serde(rename = "Originium Shard")]
+    OriginiumShard,
+    #[serde(rename = "LMD")]
+    Lmd,
+    #[serde(rename = "Orundum")]
+    Orundum,
+}
+
+impl PlanProduct {
+    fn is_manufacture(&self) -> bool {
+        !self.is_trading()
+    }
+
+    fn is_trading(&self) -> bool {
+        matches!(self, PlanProduct::Lmd | PlanProduct::Orundum)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DronesRoom {
+    Trading,
+    Manufacture,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DronesRule {
+    All,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PlanFiammetta {
+    #[serde(default)]
+    pub enable: bool,
+    /// Operator to recover the morale of
+    pub target: String,
+    pub order: PlanOrder,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PlanDrones {
+    #[serde(default)]
+    pub enable: bool,
+    pub room: DronesRoom,
+    /// 1-based index of the room
+    pub index: i32,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule: Option<DronesRule>,
+    pub order: PlanOrder,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PlanGroup {
+    pub name: String,
+    pub operators: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PlanRoom {
+    #[serde(default)]
+    pub operators: Vec<String>,
+    /// Fill the empty slots with the operators MaaCore chooses
+    #[serde(default)]
+    pub autofill: bool,
+    /// Keep the order of `operators`
+    #[serde(default)]
+    pub sort: bool,
+    #[serde(default)]
+    pub skip: bool,
+    /// Operators or groups to pick from when `operators` is not enough
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub candidates: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub product: Option<PlanProduct>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PlanRooms {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub control: Vec<PlanRoom>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub manufacture: Vec<PlanRoom>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub trading: Vec<PlanRoom>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub power: Vec<PlanRoom>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub meeting: Vec<PlanRoom>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub hire: Vec<PlanRoom>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub dormitory: Vec<PlanRoom>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub processing: Vec<PlanRoom>,
+}
+
+impl PlanRooms {
+    /// Return all the facilities with their name, the maximum number of rooms and of operators per room
+    fn facilities(&self) -> [(&'static str, &Vec<PlanRoom>, usize, usize); 8] {
+        [
+            ("control", &self.control, 1, 5),
+            ("manufacture", &self.manufacture, 5, 3),
+            ("trading", &self.trading, 5, 3),
+            ("power", &self.power, 3, 1),
+            ("meeting", &self.meeting, 1, 2),
+            ("hire", &self.hire, 1, 1),
+            ("dormitory", &self.dormitory, 4, 5),
+            ("processing", &self.processing, 1, 1),
+        ]
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct InfrastPlan {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description_post: Option<String>,
+    /// Time ranges in `HH:MM` during which the plan is used
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub period: Vec<[String; 2]>,
+    /// Duration of the plan in minutes
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<i32>,
+    #[serde(rename = "Fiammetta")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fiammetta: Option<PlanFiammetta>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub drones: Option<PlanDrones>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<PlanGroup>,
+    #[serde(default)]
+    pub rooms: PlanRooms,
+}
+
+fn is_plan_time(time: &str) -> bool {
+    match time.split_once(':') {
+        Some((hour, minute)) => {
+            hour.len() == 2
+                && minute.len() == 2
+                && hour.parse::<u32>().is_ok_and(|hour| hour < 24)
+                && minute.parse::<u32>().is_ok_and(|minute| minute < 60)
+        }
+        None => false,
+    }
+}
+
+impl InfrastPlan {
+    fn validate(&self, prefix: &str, errors: &mut Vec<ValidationError>) {
+        for (index, range) in self.period.iter().enumerate() {
+            for time in range {
+                if !is_plan_time(time) {
+                    errors.push(ValidationError::new(
+                        format!("{}.period.{}", prefix, index),
+                        format!("{} is not a time in HH:MM", time),
+                    ));
+                }
+            }
+        }
+
+        if let Some(duration) = self.duration {
+            if duration <= 0 {
+                errors.push(ValidationError::new(
+                    format!("{}.duration", prefix),
+                    "must be positive",
+                ));
+            }
+        }
+
+        if let Some(fiammetta) = &self.fiammetta {
+            if fiammetta.enable && fiammetta.target.is_empty() {
+                errors.push(ValidationError::new(
+                    format!("{}.Fiammetta.target", prefix),
+                    "must not be empty",
+                ));
+            }
+        }
+
+        if let Some(drones) = &self.drones {
+            if !(1..=5).contains(&drones.index) {
+                errors.push(ValidationError::new(
+                    format!("{}.drones.index", prefix),
+                    format!("{} is out of range", drones.index),
+                ));
+            }
+        }
+
+        let mut stationed = HashSet::new();
+        for (facility, rooms, max_rooms, max_operators) in self.rooms.facilities() {
+            if rooms.len() > max_rooms {
+                errors.push(ValidationError::new(
+                    format!("{}.rooms.{}", prefix, facility),
+                    format!("at most {} rooms", max_rooms),
+                ));
+            }
+            for (index, room) in rooms.iter().enumerate() {
+                let field = format!("{}.rooms.{}.{}", prefix, facility, index);
+                if room.operators.len() > max_operators {
+                    errors.push(ValidationError::new(
+                        format!("{}.operators", field),
+                        format!("at most {} operators", max_operators),
+                    ));
+                }
+                for operator in &room.operators {
+                    if !stationed.insert(operator.as_str()) {
+                        errors.push(ValidationError::new(
+                            format!("{}.operators", field),
+                            format!("{} is stationed in more than one room", operator),
+                        ));
+                    }
+                }
+                if let Some(product) = room.product {
+                    let valid = match facility {
+                        "manufacture" => product.is_manufacture(),
+                        "trading" => product.is_trading(),
+                        _ => false,
+                    };
+                    if !valid {
+                        errors.push(ValidationError::new(
+                            format!("{}.product", field),
+                            format!("{:?} can not be produced in {}", product, facility),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A custom infrast plan file
+///
+/// # Example
+///
+/// ```
+/// use maa_types::task::infrast_plan::InfrastPlanFile;
+///
+/// let file = InfrastPlanFile::from_json(r#"{
+///     "title": "243",
+///     "plans": [{
+///         "name": "day",
+///         "period": [["08:00", "20:00"]],
+///         "rooms": {
+///             "trading": [{ "operators": ["Texas", "Lappland"], "product": "LMD" }]
+///         }
+///     }]
+/// }"#).unwrap();
+///
+/// assert!(file.validate().is_empty());
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct InfrastPlanFile {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub plans: Vec<InfrastPlan>,
+}
+
+impl InfrastPlanFile {
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Check the plans, return all the problems found
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        if self.plans.is_empty() {
+            errors.push(ValidationError::new("plans", "must not be empty"));
+        }
+        for (index, plan) in self.plans.iter().enumerate() {
+            plan.validate(&format!("plans.{}", index), &mut errors);
+        }
+        errors
+    }
+}
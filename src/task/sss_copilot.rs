@@ -0,0 +1,205 @@
+//! Types for the SSS copilot job files used by [SSSCopilotParams](super::SSSCopilotParams)
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::copilot::{
+    validate_action, validate_operator, CopilotAction, CopilotDoc, CopilotOperator, Direction,
+};
+use super::ValidationError;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SSSFileType {
+    #[default]
+    SSS,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SSSEquipment {
+    A,
+    B,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SSSStrategy {
+    /// Pick the recommended directive
+    #[serde(rename = "优选策略")]
+    Optimal,
+    /// Pick the directive freely
+    #[serde(rename = "自由策略")]
+    Free,
+}
+
+/// Where to deploy the core operator or the tool men of a stage
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SSSStageStrategy {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub core: Option<String>,
+    /// Number of tool men to deploy per profession
+    #[serde(default)]
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub tool_men: HashMap<String, i32>,
+    pub location: [i32; 2],
+    #[serde(default)]
+    pub direction: Direction,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SSSStage {
+    pub stage_name: String,
+    #[serde(default)]
+    pub strategies: Vec<SSSStageStrategy>,
+    /// Draw operator cards whenever possible
+    #[serde(default)]
+    pub draw_as_possible: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub actions: Vec<CopilotAction>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_times: Option<i32>,
+}
+
+/// A SSS copilot job file
+///
+/// # Example
+///
+/// ```
+/// use maa_types::task::sss_copilot::SSSCopilotFile;
+///
+/// let file = SSSCopilotFile::from_json(r#"{
+///     "type": "SSS",
+///     "stage_name": "Dossoles Construction Site",
+///     "equipment": ["A", "A", "A", "A", "B", "B", "B", "B"],
+///     "strategy": "优选策略",
+///     "opers": [{ "name": "Thorns", "skill": 3, "skill_usage": 1 }],
+///     "tool_men": { "Pioneer": 13 },
+///     "stages": [{
+///         "stage_name": "Gate",
+///         "strategies": [{ "core": "Thorns", "location": [6, 4], "direction": "Left" }],
+///         "draw_as_possible": true
+///     }]
+/// }"#).unwrap();
+///
+/// assert!(file.validate().is_empty());
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SSSCopilotFile {
+    #[serde(rename = "type")]
+    #[serde(default)]
+    pub file_type: SSSFileType,
+    pub stage_name: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minimum_required: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doc: Option<CopilotDoc>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub buff: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub equipment: Vec<SSSEquipment>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<SSSStrategy>,
+    #[serde(default)]
+    pub opers: Vec<CopilotOperator>,
+    /// Number of tool men to recruit per profession
+    #[serde(default)]
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub tool_men: HashMap<String, i32>,
+    /// Operators or professions to pick from the drops, in order of priority
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub drops: Vec<String>,
+    /// Operators not to deploy
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub blacklist: Vec<String>,
+    #[serde(default)]
+    pub stages: Vec<SSSStage>,
+}
+
+fn validate_tool_men(
+    field: &str,
+    tool_men: &HashMap<String, i32>,
+    errors: &mut Vec<ValidationError>,
+) {
+    for (profession, count) in tool_men {
+        if *count < 0 {
+            errors.push(ValidationError::new(
+                format!("{}.{}", field, profession),
+                "must not be negative",
+            ));
+        }
+    }
+}
+
+impl SSSCopilotFile {
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Check the job, return all the problems found
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        if self.stage_name.is_empty() {
+            errors.push(ValidationError::new("stage_name", "must not be empty"));
+        }
+        if self.equipment.len() > 8 {
+            errors.push(ValidationError::new("equipment", "at most 8 slots"));
+        }
+
+        let mut names = HashSet::new();
+        for (index, operator) in self.opers.iter().enumerate() {
+            let field = format!("opers.{}", index);
+            if !names.insert(operator.name.as_str()) {
+                errors.push(ValidationError::new(
+                    format!("{}.name", field),
+                    format!("{} is duplicated", operator.name),
+                ));
+            }
+            validate_operator(&field, operator, &mut errors);
+        }
+        validate_tool_men("tool_men", &self.tool_men, &mut errors);
+
+        if self.stages.is_empty() {
+            errors.push(ValidationError::new("stages", "must not be empty"));
+        }
+        for (index, stage) in self.stages.iter().enumerate() {
+            let field = format!("stages.{}", index);
+            if stage.stage_name.is_empty() {
+                errors.push(ValidationError::new(
+                    format!("{}.stage_name", field),
+                    "must not be empty",
+                ));
+            }
+            for (index, strategy) in stage.strategies.iter().enumerate() {
+                let field = format!("{}.strategies.{}", field, index);
+                if strategy.core.is_none() && strategy.tool_men.is_empty() {
+                    errors.push(ValidationError::new(
+                        field.clone(),
+                        "requires either core or tool_men",
+                    ));
+                }
+                validate_tool_men(
+                    &format!("{}.tool_men", field),
+                    &strategy.tool_men,
+                    &mut errors,
+                );
+            }
+            for (index, action) in stage.actions.iter().enumerate() {
+                validate_action(&format!("{}.actions.{}", field, index), action, &mut errors);
+            }
+        }
+        errors
+    }
+}